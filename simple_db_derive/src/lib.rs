@@ -0,0 +1,69 @@
+//! simple_db 的派生宏
+//!
+//! 提供 `#[derive(FromRow)]`，为具名字段的结构体生成 `simple_db::model::convert::FromRow` 的实现：
+//! 每个字段按字段名从查询结果的同名列中取值，并通过 `FromValue` 转换为字段的类型。
+//! 字段名与列名不同时，可以用 `#[column("列名")]` 指定列名。
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// 为结构体派生 `FromRow`
+///
+/// # Arguments
+///
+/// * `input` - 结构体定义
+///
+/// # Returns
+///
+/// 生成的 `FromRow` 实现；输入不是具名字段的结构体时产生编译错误
+#[proc_macro_derive(FromRow, attributes(column))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_row(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// 生成 `FromRow` 的实现
+fn expand_from_row(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "FromRow 只能用于具名字段的结构体",
+                ))
+            }
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "FromRow 只能用于结构体")),
+    };
+
+    let mut initializers = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let mut column = ident.to_string().trim_start_matches("r#").to_string();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("column"))
+        {
+            column = attr.parse_args::<LitStr>()?.value();
+        }
+        initializers.push(quote! { #ident: row.get(#column)? });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::simple_db::model::convert::FromRow for #name #ty_generics #where_clause {
+            fn from_row(
+                row: &::simple_db::model::convert::Row,
+            ) -> ::std::result::Result<Self, ::simple_db::executor::ExecutionError> {
+                ::std::result::Result::Ok(Self {
+                    #(#initializers,)*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,82 @@
+/// 数据库句柄模块
+///
+/// 为嵌入使用提供类型化的接口：SQL 语句通过预处理语句执行，参数以 `ToValue` 绑定，
+/// 查询结果可以通过 `FromRow` 直接转换为结构体。
+use std::sync::atomic::Ordering;
+
+use crate::executor::error::ExecutionResult;
+use crate::executor::prepare;
+use crate::executor::QueryResult;
+use crate::model::convert::{FromRow, Row, ToValue};
+use crate::utils::IS_INFO_OUTPUT;
+
+/// 数据库句柄
+///
+/// 所有句柄共享同一个全局的表集合。
+#[derive(Debug)]
+pub struct Database;
+
+impl Database {
+    /// 创建数据库句柄，并关闭信息输出
+    pub fn new() -> Self {
+        IS_INFO_OUTPUT.store(false, Ordering::Relaxed);
+        Database
+    }
+
+    /// 执行一条 SQL 语句
+    ///
+    /// # Arguments
+    ///
+    /// * `sql` - SQL 语句，可以包含 `?` 或 `$1` 形式的参数占位符
+    /// * `params` - 参数值，依次对应各个参数
+    ///
+    /// # Returns
+    ///
+    /// * `ExecutionResult<()>` - 执行结果
+    pub fn execute(&self, sql: &str, params: &[&dyn ToValue]) -> ExecutionResult<()> {
+        prepare::prepare(sql)?.execute(params)
+    }
+
+    /// 执行一条查询语句，返回查询结果
+    ///
+    /// # Arguments
+    ///
+    /// * `sql` - 查询语句，可以包含 `?` 或 `$1` 形式的参数占位符
+    /// * `params` - 参数值，依次对应各个参数
+    ///
+    /// # Returns
+    ///
+    /// * `ExecutionResult<QueryResult>` - 查询结果
+    pub fn query(&self, sql: &str, params: &[&dyn ToValue]) -> ExecutionResult<QueryResult> {
+        prepare::prepare(sql)?.query(params)
+    }
+
+    /// 执行一条查询语句，将结果的每一行转换为指定的类型
+    ///
+    /// # Arguments
+    ///
+    /// * `sql` - 查询语句，可以包含 `?` 或 `$1` 形式的参数占位符
+    /// * `params` - 参数值，依次对应各个参数
+    ///
+    /// # Returns
+    ///
+    /// * `ExecutionResult<Vec<T>>` - 转换后的各行，任一行转换失败时返回错误
+    pub fn query_as<T: FromRow>(
+        &self,
+        sql: &str,
+        params: &[&dyn ToValue],
+    ) -> ExecutionResult<Vec<T>> {
+        let result = self.query(sql, params)?;
+        result
+            .rows
+            .iter()
+            .map(|values| T::from_row(&Row::new(&result.columns, values)))
+            .collect()
+    }
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Self::new()
+    }
+}
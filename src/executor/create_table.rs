@@ -1,16 +1,24 @@
 /// 创建表操作模块
 ///
 /// 实现 CREATE TABLE 语句的解析和执行逻辑，负责创建数据库表的结构。
-use sqlparser::ast::{CharacterLength, CreateTable, DataType, Statement};
+/// 除普通的列定义外，还支持 `IF NOT EXISTS`、`CREATE TABLE ... AS SELECT`
+/// 以及 `CREATE TABLE ... LIKE` 三种形式。
+use sqlparser::ast::{CharacterLength, CreateTable, DataType, ObjectName, Statement};
 
+use crate::executor::information_schema::ensure_writable;
+use crate::executor::query::execute_query;
+use crate::executor::query_result::QueryResult;
 use crate::executor::table::Table;
 use crate::executor::{ExecutionError, ExecutionResult, TABLES};
-use crate::model::{Column, ColumnDataType as TableDataType};
-use crate::utils::log_info;
+use crate::model::{Column, ColumnDataType as TableDataType, Value};
+use crate::utils::{log_info, log_warning};
 
 /// 执行创建表操作
 ///
 /// 解析 CREATE TABLE 语句，验证表是否存在，然后创建新表。
+/// 若指定了 `IF NOT EXISTS` 且表已存在，则跳过创建并给出警告；
+/// 若带有 `AS SELECT` 子句，则根据查询结果推导列定义并填充数据；
+/// 若带有 `LIKE` 子句，则复制源表的列定义及约束。
 ///
 /// # Arguments
 ///
@@ -21,21 +29,43 @@ use crate::utils::log_info;
 /// * `ExecutionResult<()>` - 创建表的结果，成功或失败
 pub fn create_table(stmt: &Statement) -> ExecutionResult<()> {
     if let Statement::CreateTable(create_table_stmt) = stmt {
-        let table_name = create_table_stmt
-            .name
-            .0
-            .iter()
-            .map(|ident| ident.to_string())
-            .collect::<Vec<String>>()
-            .join("_");
+        let table_name = table_name_of(&create_table_stmt.name);
+        ensure_writable(&create_table_stmt.name.to_string())?;
+
+        if TABLES.lock().unwrap().contains_key(&table_name) {
+            if create_table_stmt.if_not_exists {
+                log_warning(format!("表 '{}' 已存在，跳过创建", table_name));
+                return Ok(());
+            }
+            return Err(ExecutionError::TableExists(table_name));
+        }
+
+        // 查询需在锁定 TABLES 之前执行，避免重复加锁
+        let query_result = match &create_table_stmt.query {
+            Some(query) => Some(execute_query(query)?),
+            None => None,
+        };
 
         let mut tables = TABLES.lock().unwrap();
+        // 执行查询期间释放了锁，需要重新检查表是否已被创建
         if tables.contains_key(&table_name) {
+            if create_table_stmt.if_not_exists {
+                log_warning(format!("表 '{}' 已存在，跳过创建", table_name));
+                return Ok(());
+            }
             return Err(ExecutionError::TableExists(table_name));
         }
-
-        let columns = create_table_columns(create_table_stmt);
-        let table = Table::new(table_name.clone(), columns);
+        let table = if let Some(like_name) = &create_table_stmt.like {
+            let like_name = table_name_of(like_name);
+            let source = tables
+                .get(&like_name)
+                .ok_or(ExecutionError::TableNotFound(like_name))?;
+            Table::new(table_name.clone(), source.columns.clone())
+        } else if let Some(query_result) = query_result {
+            create_table_from_query(table_name.clone(), create_table_stmt, query_result)?
+        } else {
+            Table::new(table_name.clone(), create_table_columns(create_table_stmt))
+        };
 
         tables.insert(table_name.clone(), table);
 
@@ -46,7 +76,81 @@ pub fn create_table(stmt: &Statement) -> ExecutionResult<()> {
     }
 }
 
-// TODO
+/// 根据查询结果创建表
+///
+/// 若语句中显式给出了列定义，则使用这些定义，并要求其数量与查询结果的列数一致；
+/// 否则根据查询结果的列名和值推导列定义。推导出的列均可为 NULL，且不带主键约束。
+/// 布尔值会以 `1`/`0` 的整数形式写入，浮点数以字符串形式写入。
+///
+/// # Arguments
+///
+/// * `table_name` - 新表的表名
+/// * `create_table_stmt` - CREATE TABLE 语句
+/// * `query_result` - `AS SELECT` 子句的查询结果
+///
+/// # Returns
+///
+/// * `ExecutionResult<Table>` - 填充好数据的新表
+fn create_table_from_query(
+    table_name: String,
+    create_table_stmt: &CreateTable,
+    query_result: QueryResult,
+) -> ExecutionResult<Table> {
+    let columns = if create_table_stmt.columns.is_empty() {
+        query_result.derive_columns()
+    } else {
+        let columns = create_table_columns(create_table_stmt);
+        if columns.len() != query_result.columns.len() {
+            return Err(ExecutionError::ExecutionError(format!(
+                "列定义数量 ({}) 与查询结果列数 ({}) 不匹配",
+                columns.len(),
+                query_result.columns.len()
+            )));
+        }
+        columns
+    };
+
+    let mut table = Table::new(table_name, columns);
+    for row in query_result.rows {
+        let row = row
+            .into_iter()
+            .map(|value| match value {
+                Value::Bool(b) => Value::Int(b as i64),
+                Value::Float(f) => Value::Varchar(f.to_string()),
+                other => other,
+            })
+            .collect();
+        table.insert_row(row)?;
+    }
+    Ok(table)
+}
+
+/// 将语句中的表名转换为内部使用的表名，多段名称以下划线连接
+///
+/// # Arguments
+///
+/// * `name` - 语句中的表名
+///
+/// # Returns
+///
+/// * `String` - 内部使用的表名
+fn table_name_of(name: &ObjectName) -> String {
+    name.0
+        .iter()
+        .map(|ident| ident.to_string())
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
+/// 根据语句中的列定义创建列
+///
+/// # Arguments
+///
+/// * `create_table_stmt` - CREATE TABLE 语句
+///
+/// # Returns
+///
+/// * `Vec<Column>` - 列定义列表
 fn create_table_columns(create_table_stmt: &CreateTable) -> Vec<Column> {
     create_table_stmt
         .columns
@@ -61,7 +165,16 @@ fn create_table_columns(create_table_stmt: &CreateTable) -> Vec<Column> {
                     };
                     TableDataType::Varchar(length)
                 }
-                DataType::Int(opt) => TableDataType::Int(opt.clone()),
+                DataType::Int(opt) | DataType::Integer(opt) => TableDataType::Int(*opt),
+                DataType::TinyInt(opt) => TableDataType::TinyInt(*opt),
+                DataType::SmallInt(opt) => TableDataType::SmallInt(*opt),
+                DataType::BigInt(opt) => TableDataType::BigInt(*opt),
+                DataType::TinyIntUnsigned(opt) => TableDataType::TinyIntUnsigned(*opt),
+                DataType::SmallIntUnsigned(opt) => TableDataType::SmallIntUnsigned(*opt),
+                DataType::IntUnsigned(opt) | DataType::IntegerUnsigned(opt) => {
+                    TableDataType::IntUnsigned(*opt)
+                }
+                DataType::BigIntUnsigned(opt) => TableDataType::BigIntUnsigned(*opt),
                 _ => TableDataType::Varchar(None),
             };
 
@@ -0,0 +1,190 @@
+/// 公共表表达式模块
+///
+/// 负责物化 `WITH` 子句中定义的公共表表达式（CTE）。每个 CTE 的查询结果被转换为临时表，
+/// 在其后定义的 CTE 和主查询中可以像普通表一样引用。`WITH RECURSIVE` 中引用自身的 CTE
+/// 通过迭代求值：先执行初始部分，再反复以上一轮新产生的行作为自身执行递归部分，直到不再产生新行。
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{visit_relations, Cte, SetExpr, SetOperator, SetQuantifier, With};
+
+use super::query::{execute_query_with, execute_set_expr};
+use super::query_result::QueryResult;
+use super::table::Table;
+use super::{ExecutionError, ExecutionResult};
+use crate::model::Value;
+use crate::utils::expr_evaluator::EvalContext;
+
+/// 递归 CTE 的最大迭代次数，与 MySQL `cte_max_recursion_depth` 的默认值一致
+const MAX_RECURSION_DEPTH: usize = 1000;
+
+/// 一个 `WITH` 子句物化出的公共表表达式
+///
+/// 嵌套查询中的 `WITH` 子句会形成新的一层，解析表名时由内向外查找。
+#[derive(Debug)]
+pub struct CteTables<'a> {
+    /// 本层定义的 CTE，以名称为键
+    tables: HashMap<String, Table>,
+    /// 外层查询中可见的 CTE
+    parent: Option<&'a CteTables<'a>>,
+}
+
+impl CteTables<'_> {
+    /// 按名称查找可见的 CTE
+    ///
+    /// # Arguments
+    /// * `name` - CTE 名称
+    ///
+    /// # Returns
+    /// * `Option<&Table>` - CTE 物化出的临时表，不存在时返回 None
+    pub fn get(&self, name: &str) -> Option<&Table> {
+        self.tables
+            .get(name)
+            .or_else(|| self.parent.and_then(|parent| parent.get(name)))
+    }
+}
+
+/// 按定义顺序物化 `WITH` 子句中的所有 CTE
+///
+/// # Arguments
+/// * `with` - `WITH` 子句
+/// * `env` - 查询的执行环境，提供表集合、外层可见的 CTE 和外层查询上下文
+///
+/// # Returns
+/// * `ExecutionResult<CteTables>` - 物化后的 CTE；名称重复或任一 CTE 执行失败时返回错误
+pub fn materialize<'a>(with: &With, env: &EvalContext<'a>) -> ExecutionResult<CteTables<'a>> {
+    let mut ctes = CteTables {
+        tables: HashMap::new(),
+        parent: env.ctes,
+    };
+    for cte in &with.cte_tables {
+        let name = &cte.alias.name.value;
+        if ctes.tables.contains_key(name) {
+            return Err(ExecutionError::ExecutionError(format!(
+                "CTE 名称 '{}' 重复",
+                name
+            )));
+        }
+        let table = match recursive_parts(with, cte) {
+            Some((anchor, recursive, is_distinct)) => {
+                materialize_recursive(cte, anchor, recursive, is_distinct, &mut ctes, env)?
+            }
+            None => {
+                execute_query_with(&cte.query, &env.with_ctes(&ctes))?.into_table(&cte.alias)?
+            }
+        };
+        ctes.tables.insert(name.clone(), table);
+    }
+    Ok(ctes)
+}
+
+/// 拆分递归 CTE 的初始部分和递归部分
+///
+/// 只有 `WITH RECURSIVE` 中形如 `初始部分 UNION [ALL] 递归部分`、且递归部分引用了自身的 CTE
+/// 才按递归方式求值，其余 CTE 按普通 CTE 处理。
+///
+/// # Returns
+/// * `Option<(&SetExpr, &SetExpr, bool)>` - 初始部分、递归部分，以及是否需要去重（`UNION` 而非 `UNION ALL`）
+fn recursive_parts<'q>(with: &With, cte: &'q Cte) -> Option<(&'q SetExpr, &'q SetExpr, bool)> {
+    if !with.recursive {
+        return None;
+    }
+    match &*cte.query.body {
+        SetExpr::SetOperation {
+            op: SetOperator::Union,
+            set_quantifier,
+            left,
+            right,
+        } => {
+            let is_self_referencing = visit_relations(&**right, |relation| {
+                if relation.to_string() == cte.alias.name.value {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+            .is_break();
+            is_self_referencing.then_some((
+                &**left,
+                &**right,
+                !matches!(set_quantifier, SetQuantifier::All),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// 迭代求值递归 CTE
+///
+/// 每一轮中，CTE 自身只包含上一轮新产生的行；`UNION` 会丢弃已经产生过的行，
+/// 当某一轮不再产生新行时迭代结束。
+///
+/// # Arguments
+/// * `cte` - 递归 CTE 的定义
+/// * `anchor` - 初始部分
+/// * `recursive` - 递归部分
+/// * `is_distinct` - 是否对结果行去重
+/// * `ctes` - 已物化的 CTE，迭代过程中用它临时存放 CTE 自身
+/// * `env` - 查询的执行环境
+///
+/// # Returns
+/// * `ExecutionResult<Table>` - CTE 的全部结果行组成的临时表
+fn materialize_recursive(
+    cte: &Cte,
+    anchor: &SetExpr,
+    recursive: &SetExpr,
+    is_distinct: bool,
+    ctes: &mut CteTables,
+    env: &EvalContext,
+) -> ExecutionResult<Table> {
+    if cte.query.order_by.is_some() || cte.query.limit.is_some() || cte.query.offset.is_some() {
+        return Err(ExecutionError::ExecutionError(format!(
+            "递归 CTE '{}' 不支持 ORDER BY、LIMIT 或 OFFSET",
+            cte.alias.name.value
+        )));
+    }
+    let name = cte.alias.name.value.clone();
+    let mut seen = HashSet::new();
+    let mut keep_new = |rows: Vec<Vec<Value>>| -> Vec<Vec<Value>> {
+        if is_distinct {
+            rows.into_iter()
+                .filter(|row| seen.insert(row.clone()))
+                .collect()
+        } else {
+            rows
+        }
+    };
+
+    let mut result = execute_set_expr(anchor, &env.with_ctes(ctes))?;
+    result.rows = keep_new(result.rows);
+    let width = result.columns.len();
+    let mut working = result.rows.clone();
+    let mut depth = 0;
+    while !working.is_empty() {
+        if depth == MAX_RECURSION_DEPTH {
+            return Err(ExecutionError::ExecutionError(format!(
+                "递归 CTE '{}' 超过最大递归深度 {}",
+                name, MAX_RECURSION_DEPTH
+            )));
+        }
+        depth += 1;
+
+        let working_table = QueryResult::new(result.columns.clone(), working);
+        ctes.tables
+            .insert(name.clone(), working_table.into_table(&cte.alias)?);
+
+        let step = execute_set_expr(recursive, &env.with_ctes(ctes))?;
+        if step.columns.len() != width {
+            return Err(ExecutionError::ExecutionError(format!(
+                "递归 CTE '{}' 的递归部分列数 ({}) 与初始部分列数 ({}) 不一致",
+                name,
+                step.columns.len(),
+                width
+            )));
+        }
+        working = keep_new(step.rows);
+        result.rows.extend(working.iter().cloned());
+    }
+    ctes.tables.remove(&name);
+    result.into_table(&cte.alias)
+}
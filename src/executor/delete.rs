@@ -1,7 +1,9 @@
 /// 删除操作模块
 ///
 /// 实现 DELETE FROM 语句的解析和执行逻辑，负责从表中删除数据。
+use crate::executor::information_schema::ensure_writable;
 use crate::executor::{ExecutionError, ExecutionResult, TABLES};
+use crate::utils::expr_evaluator::EvalContext;
 use sqlparser::ast::{FromTable, Statement, TableFactor};
 
 /// 执行删除操作
@@ -30,11 +32,11 @@ pub fn delete(stmt: &Statement) -> ExecutionResult<()> {
                     ));
                 }
             };
+            ensure_writable(&table_name)?;
             let mut tables = TABLES.lock().unwrap();
-            if tables.contains_key(&table_name) == false {
-                return Err(ExecutionError::TableNotFound(table_name));
-            }
-            let table = tables.get_mut(&table_name).unwrap();
+            let table = tables
+                .get(&table_name)
+                .ok_or(ExecutionError::TableNotFound(table_name.clone()))?;
             let where_clause = &delete.selection;
             /*let limit = match &delete.limit {
                 Some(exp) => match exp {
@@ -47,7 +49,13 @@ pub fn delete(stmt: &Statement) -> ExecutionResult<()> {
                 None => 0,
             };*/
 
-            table.delete_rows(where_clause)?
+            // 先确定要删除的行，条件中的子查询可以读取所有表
+            let ctx = EvalContext::default().with_catalog(&tables);
+            let matching_row_indices = table.filter_rows_in(&ctx, where_clause)?;
+            tables
+                .get_mut(&table_name)
+                .unwrap()
+                .delete_row_indices(matching_row_indices);
         }
     } else {
         return Err(ExecutionError::ParseError("无法解析DELETE语句".to_string()));
@@ -1,6 +1,7 @@
 /// 表删除操作模块
 ///
 /// 实现 DROP TABLE 语句的解析和执行逻辑，负责删除数据库表。
+use crate::executor::information_schema::ensure_writable;
 use crate::executor::storage::remove_table_file;
 use crate::executor::{ExecutionError, ExecutionResult, TABLES};
 use crate::utils;
@@ -33,6 +34,7 @@ pub fn drop(stmt: &Statement) -> ExecutionResult<()> {
 
                 for name in names {
                     let table_name = name.to_string();
+                    ensure_writable(&table_name)?;
 
                     if tables.remove(&table_name).is_some() || *if_exists {
                         remove_table_file(&table_name)?;
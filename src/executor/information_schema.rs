@@ -0,0 +1,241 @@
+/// information_schema 虚拟目录模块
+///
+/// 根据 `TABLES` 中的表定义按需生成只读的虚拟表，
+/// 使外部工具可以通过普通的 `SELECT` 语句查询数据库的元数据。
+/// 支持 `information_schema.tables`、`columns`、`table_constraints` 和 `statistics`。
+use std::collections::HashMap;
+
+use crate::executor::table::Table;
+use crate::executor::{ExecutionError, ExecutionResult};
+use crate::model::{Column, ColumnDataType, Value};
+
+/// 虚拟目录的模式名
+const SCHEMA_NAME: &str = "information_schema";
+
+/// 用户表所属的模式名
+const TABLE_SCHEMA: &str = "simple_db";
+
+/// 判断表名是否指向 information_schema 中的虚拟表
+///
+/// # Arguments
+///
+/// * `table_name` - 以 `.` 分隔的完整表名
+///
+/// # Returns
+///
+/// * `bool` - 表名以 `information_schema.` 开头时返回 true
+pub fn is_virtual_table(table_name: &str) -> bool {
+    split_virtual_name(table_name).is_some()
+}
+
+/// 确保表名不指向只读的虚拟表
+///
+/// 供 INSERT、UPDATE、DELETE 等写操作在执行前调用。
+///
+/// # Arguments
+///
+/// * `table_name` - 要写入的表名
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 虚拟表返回错误，其它表返回 Ok(())
+pub fn ensure_writable(table_name: &str) -> ExecutionResult<()> {
+    if is_virtual_table(table_name) {
+        return Err(ExecutionError::ExecutionError(format!(
+            "表 '{}' 属于只读的 {}",
+            table_name, SCHEMA_NAME
+        )));
+    }
+    Ok(())
+}
+
+/// 生成虚拟表
+///
+/// # Arguments
+///
+/// * `table_name` - 以 `.` 分隔的完整表名，例如 `information_schema.columns`
+/// * `tables` - 当前数据库中的所有表
+///
+/// # Returns
+///
+/// * `ExecutionResult<Table>` - 根据当前表定义生成的虚拟表
+///
+/// # Errors
+/// * `ExecutionError::TableNotFound` - 如果 information_schema 中没有该表
+pub fn build_virtual_table(
+    table_name: &str,
+    tables: &HashMap<String, Table>,
+) -> ExecutionResult<Table> {
+    let mut user_tables: Vec<&Table> = tables.values().collect();
+    user_tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let (columns, data) = match split_virtual_name(table_name) {
+        Some("tables") => tables_table(&user_tables),
+        Some("columns") => columns_table(&user_tables),
+        Some("table_constraints") => table_constraints_table(&user_tables),
+        Some("statistics") => statistics_table(&user_tables),
+        _ => return Err(ExecutionError::TableNotFound(table_name.to_string())),
+    };
+
+    let mut table = Table::new(table_name.to_string(), columns);
+    table.data = data;
+    Ok(table)
+}
+
+/// 拆分虚拟表名
+///
+/// 模式名不是 information_schema 时返回 None；模式名匹配时返回小写的虚拟表名，
+/// 虚拟表不存在时返回空字符串。
+fn split_virtual_name(table_name: &str) -> Option<&'static str> {
+    let (schema, name) = table_name.split_once('.')?;
+    if !schema.eq_ignore_ascii_case(SCHEMA_NAME) {
+        return None;
+    }
+    ["tables", "columns", "table_constraints", "statistics"]
+        .into_iter()
+        .find(|virtual_name| virtual_name.eq_ignore_ascii_case(name))
+        .or(Some(""))
+}
+
+/// 构造虚拟表的列定义，整数列以 `#` 前缀标记
+fn virtual_columns(names: &[&str]) -> Vec<Column> {
+    names
+        .iter()
+        .map(|name| {
+            let (name, data_type) = match name.strip_prefix('#') {
+                Some(name) => (name, ColumnDataType::Int(None)),
+                None => (*name, ColumnDataType::Varchar(None)),
+            };
+            Column {
+                name: name.to_string(),
+                data_type,
+                is_primary_key: false,
+                is_nullable: true,
+            }
+        })
+        .collect()
+}
+
+/// 字符串值的简写
+fn text(s: &str) -> Value {
+    Value::Varchar(s.to_string())
+}
+
+/// `information_schema.tables`：每张表一行
+fn tables_table(tables: &[&Table]) -> (Vec<Column>, Vec<Vec<Value>>) {
+    let columns = virtual_columns(&["table_schema", "table_name", "table_type", "#table_rows"]);
+    let data = tables
+        .iter()
+        .map(|table| {
+            vec![
+                text(TABLE_SCHEMA),
+                text(&table.name),
+                text("BASE TABLE"),
+                Value::Int(table.data.len() as i64),
+            ]
+        })
+        .collect();
+    (columns, data)
+}
+
+/// `information_schema.columns`：每列一行
+fn columns_table(tables: &[&Table]) -> (Vec<Column>, Vec<Vec<Value>>) {
+    let columns = virtual_columns(&[
+        "table_schema",
+        "table_name",
+        "column_name",
+        "#ordinal_position",
+        "column_default",
+        "is_nullable",
+        "data_type",
+        "#character_maximum_length",
+        "column_type",
+        "column_key",
+    ]);
+    let data = tables
+        .iter()
+        .flat_map(|table| {
+            table.columns.iter().enumerate().map(|(i, col)| {
+                let max_length = match col.data_type {
+                    ColumnDataType::Varchar(len) => {
+                        len.map_or(Value::Null, |len| Value::Int(len as i64))
+                    }
+                    _ => Value::Null,
+                };
+                let data_type = col.data_type.name().to_lowercase();
+                let is_nullable = col.is_nullable && !col.is_primary_key;
+                vec![
+                    text(TABLE_SCHEMA),
+                    text(&table.name),
+                    text(&col.name),
+                    Value::Int(i as i64 + 1),
+                    Value::Null,
+                    text(if is_nullable { "YES" } else { "NO" }),
+                    text(&data_type),
+                    max_length,
+                    text(&col.data_type.to_string().to_lowercase()),
+                    text(if col.is_primary_key { "PRI" } else { "" }),
+                ]
+            })
+        })
+        .collect();
+    (columns, data)
+}
+
+/// `information_schema.table_constraints`：每个带主键的表一行
+fn table_constraints_table(tables: &[&Table]) -> (Vec<Column>, Vec<Vec<Value>>) {
+    let columns = virtual_columns(&[
+        "constraint_schema",
+        "constraint_name",
+        "table_schema",
+        "table_name",
+        "constraint_type",
+    ]);
+    let data = tables
+        .iter()
+        .filter(|table| table.columns.iter().any(|col| col.is_primary_key))
+        .map(|table| {
+            vec![
+                text(TABLE_SCHEMA),
+                text("PRIMARY"),
+                text(TABLE_SCHEMA),
+                text(&table.name),
+                text("PRIMARY KEY"),
+            ]
+        })
+        .collect();
+    (columns, data)
+}
+
+/// `information_schema.statistics`：主键索引中的每列一行
+fn statistics_table(tables: &[&Table]) -> (Vec<Column>, Vec<Vec<Value>>) {
+    let columns = virtual_columns(&[
+        "table_schema",
+        "table_name",
+        "#non_unique",
+        "index_name",
+        "#seq_in_index",
+        "column_name",
+    ]);
+    let data = tables
+        .iter()
+        .flat_map(|table| {
+            table
+                .columns
+                .iter()
+                .filter(|col| col.is_primary_key)
+                .enumerate()
+                .map(|(i, col)| {
+                    vec![
+                        text(TABLE_SCHEMA),
+                        text(&table.name),
+                        Value::Int(0),
+                        text("PRIMARY"),
+                        Value::Int(i as i64 + 1),
+                        text(&col.name),
+                    ]
+                })
+        })
+        .collect();
+    (columns, data)
+}
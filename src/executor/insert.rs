@@ -1,34 +1,38 @@
 /// 数据插入操作模块
 ///
 /// 实现 INSERT INTO 语句的解析和执行逻辑，负责向表中插入数据行。
+use crate::executor::information_schema::ensure_writable;
 use crate::executor::{ExecutionError, ExecutionResult, TABLES};
 use crate::model::Value as TableValue;
 use crate::utils;
+use crate::utils::expr_evaluator::{EvalContext, ExprEvaluator};
 use sqlparser::ast::{Expr, SetExpr, Statement, Value, Values};
 
-fn extract_row_values(expr: &Expr) -> TableValue {
-    match expr {
+fn extract_row_values(expr: &Expr) -> ExecutionResult<TableValue> {
+    Ok(match expr {
         Expr::Value(val) => match &val.value {
             Value::SingleQuotedString(s) => TableValue::Varchar(s.clone()),
             Value::DoubleQuotedString(s) => TableValue::Varchar(s.clone()),
-            Value::Number(n, _) => TableValue::Int(n.parse::<i64>().unwrap()),
+            Value::Number(n, _) => ExprEvaluator::parse_number(n)?,
+            Value::Boolean(b) => TableValue::Bool(*b),
             Value::Null => TableValue::Null,
             _ => TableValue::Varchar(val.to_string()),
         },
         Expr::Identifier(ident) => TableValue::Varchar(ident.value.clone()),
+        // 负数字面量被解析为一元运算
+        Expr::UnaryOp { .. } | Expr::Nested(_) => {
+            ExprEvaluator::evaluate(&EvalContext::default(), expr)?
+        }
         _ => TableValue::Varchar(expr.to_string()),
-    }
+    })
 }
 
-fn extract_rows_to_insert(values: &Values) -> Vec<Vec<TableValue>> {
-    let mut data_to_insert = Vec::new();
-
-    for row in &values.rows {
-        let row_values: Vec<TableValue> = row.iter().map(extract_row_values).collect();
-        data_to_insert.push(row_values);
-    }
-
-    data_to_insert
+fn extract_rows_to_insert(values: &Values) -> ExecutionResult<Vec<Vec<TableValue>>> {
+    values
+        .rows
+        .iter()
+        .map(|row| row.iter().map(extract_row_values).collect())
+        .collect()
 }
 
 /// 重新排序插入数据，使其与表结构列顺序一致
@@ -108,6 +112,7 @@ fn reorder_insert_data(
 pub fn insert(stmt: &Statement) -> ExecutionResult<()> {
     if let Statement::Insert(insert_stmt) = stmt {
         let table_name = insert_stmt.table.to_string();
+        ensure_writable(&table_name)?;
         let column_names: Vec<String> = insert_stmt
             .columns
             .iter()
@@ -115,7 +120,7 @@ pub fn insert(stmt: &Statement) -> ExecutionResult<()> {
             .collect();
 
         let data_to_insert = match insert_stmt.source.as_ref().unwrap().body.as_ref() {
-            SetExpr::Values(values) => extract_rows_to_insert(values),
+            SetExpr::Values(values) => extract_rows_to_insert(values)?,
             _ => {
                 return Err(ExecutionError::ParseError(
                     "无法解析 INSERT 语句".to_string(),
@@ -0,0 +1,666 @@
+/// 多表连接模块
+///
+/// 实现 `FROM` 子句中的多表连接：`INNER`、`LEFT`、`RIGHT`、`FULL`、`CROSS JOIN`、
+/// 逗号分隔的交叉连接，以及 `ON`、`USING` 和 `NATURAL` 连接条件。
+/// 连接结果被物化为一张中间表，并通过 [`Scope`] 描述每一列来自哪张表，
+/// 之后的过滤、分组、排序和投影与单表查询共用同一套流程。
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{
+    BinaryOperator, Expr, Ident, Join, JoinConstraint, JoinOperator, Query, TableFactor,
+    TableWithJoins, Visit, Visitor,
+};
+
+use super::query::load_relation;
+use super::table::Table;
+use super::{ExecutionError, ExecutionResult};
+use crate::model::Value;
+use crate::utils::expr_evaluator::{EvalContext, ExprEvaluator};
+
+/// 连接结果的列作用域
+///
+/// 记录连接结果中每张表的限定名及其列所在的位置，用于解析 `t.col` 和 `col` 形式的列引用。
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    /// 参与连接的表，按其列在连接结果中的顺序排列
+    pub sources: Vec<ScopeSource>,
+    /// `USING` 或 `NATURAL` 连接合并的同名列
+    pub merged: Vec<MergedColumn>,
+}
+
+/// 作用域中的一张表
+#[derive(Debug, Clone)]
+pub struct ScopeSource {
+    /// 表的限定名：有别名时为别名，否则为表名
+    pub qualifier: String,
+    /// 表的第一列在连接结果中的位置
+    pub offset: usize,
+    /// 表的列名
+    pub columns: Vec<String>,
+}
+
+/// `USING` 或 `NATURAL` 连接合并的列
+///
+/// 不带限定名引用该列时，取各来源列中第一个非 NULL 的值，
+/// 因此外连接中没有匹配的一侧不影响该列的值。
+#[derive(Debug, Clone)]
+pub struct MergedColumn {
+    /// 列名
+    pub name: String,
+    /// 各来源列在连接结果中的位置
+    pub indices: Vec<usize>,
+}
+
+impl Scope {
+    /// 创建只包含一张表的作用域
+    ///
+    /// # Arguments
+    /// * `qualifier` - 表的限定名
+    /// * `table` - 表对象
+    pub fn from_table(qualifier: &str, table: &Table) -> Self {
+        Self {
+            sources: vec![ScopeSource {
+                qualifier: qualifier.to_string(),
+                offset: 0,
+                columns: table.columns.iter().map(|col| col.name.clone()).collect(),
+            }],
+            merged: vec![],
+        }
+    }
+
+    /// 解析列引用
+    ///
+    /// # Arguments
+    /// * `qualifier` - 可选的表限定名
+    /// * `column_name` - 列名
+    ///
+    /// # Returns
+    /// * `Result<Vec<usize>, ExecutionError>` - 列在连接结果中的位置；
+    ///   合并列可能有多个位置，取第一个非 NULL 的值
+    ///
+    /// # Errors
+    /// * 限定名不存在、列不存在，或者不带限定名的列名在多张表中存在
+    pub fn resolve(
+        &self,
+        qualifier: Option<&str>,
+        column_name: &str,
+    ) -> Result<Vec<usize>, ExecutionError> {
+        if let Some(qualifier) = qualifier {
+            let source = self
+                .sources
+                .iter()
+                .find(|source| source.qualifier == qualifier)
+                .ok_or(ExecutionError::ExecutionError(format!(
+                    "未知的表限定名 '{}'",
+                    qualifier
+                )))?;
+            return match source.columns.iter().position(|col| col == column_name) {
+                Some(idx) => Ok(vec![source.offset + idx]),
+                None => Err(ExecutionError::ExecutionError(format!(
+                    "列 '{}' 在表 '{}' 中不存在",
+                    column_name, qualifier
+                ))),
+            };
+        }
+
+        if let Some(merged) = self.merged.iter().find(|col| col.name == column_name) {
+            return Ok(merged.indices.clone());
+        }
+        let mut found = self.sources.iter().filter_map(|source| {
+            source
+                .columns
+                .iter()
+                .position(|col| col == column_name)
+                .map(|idx| source.offset + idx)
+        });
+        match (found.next(), found.next()) {
+            (Some(idx), None) => Ok(vec![idx]),
+            (Some(_), Some(_)) => Err(ExecutionError::ExecutionError(format!(
+                "列 '{}' 不明确，请使用表名限定",
+                column_name
+            ))),
+            (None, _) => Err(ExecutionError::ExecutionError(format!(
+                "列 '{}' 不存在",
+                column_name
+            ))),
+        }
+    }
+
+    /// 计算通配符展开后的列
+    ///
+    /// 与 MySQL 一致，`*` 先列出合并列，再按表的顺序列出其余的列；
+    /// `t.*` 列出表 `t` 的所有列。
+    ///
+    /// # Arguments
+    /// * `qualifier` - 可选的表限定名，对应 `t.*`
+    ///
+    /// # Returns
+    /// * `Result<Vec<(String, Vec<usize>)>, ExecutionError>` - 每列的列名及其位置
+    pub fn wildcard(
+        &self,
+        qualifier: Option<&str>,
+    ) -> Result<Vec<(String, Vec<usize>)>, ExecutionError> {
+        let source_columns = |source: &ScopeSource| {
+            source
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(idx, col)| (col.clone(), vec![source.offset + idx]))
+                .collect::<Vec<_>>()
+        };
+        if let Some(qualifier) = qualifier {
+            let source = self
+                .sources
+                .iter()
+                .find(|source| source.qualifier == qualifier)
+                .ok_or(ExecutionError::ExecutionError(format!(
+                    "未知的表限定名 '{}'",
+                    qualifier
+                )))?;
+            return Ok(source_columns(source));
+        }
+        let merged_indices: HashSet<usize> = self
+            .merged
+            .iter()
+            .flat_map(|col| col.indices.iter().copied())
+            .collect();
+        let mut columns: Vec<(String, Vec<usize>)> = self
+            .merged
+            .iter()
+            .map(|col| (col.name.clone(), col.indices.clone()))
+            .collect();
+        columns.extend(
+            self.sources
+                .iter()
+                .flat_map(source_columns)
+                .filter(|(_, indices)| !merged_indices.contains(&indices[0])),
+        );
+        Ok(columns)
+    }
+
+    /// 取出列在一行中的值
+    ///
+    /// # Arguments
+    /// * `row` - 连接结果中的一行
+    /// * `indices` - [`Scope::resolve`] 返回的列位置
+    pub fn value(row: &[Value], indices: &[usize]) -> Value {
+        indices
+            .iter()
+            .map(|&idx| &row[idx])
+            .find(|value| !matches!(value, Value::Null))
+            .cloned()
+            .unwrap_or(Value::Null)
+    }
+
+    /// 返回将另一作用域追加到当前作用域之后得到的作用域
+    fn concat(&self, other: &Scope, offset: usize) -> Result<Scope, ExecutionError> {
+        let mut scope = self.clone();
+        for source in &other.sources {
+            if scope
+                .sources
+                .iter()
+                .any(|existing| existing.qualifier == source.qualifier)
+            {
+                return Err(ExecutionError::ExecutionError(format!(
+                    "表名或别名 '{}' 重复",
+                    source.qualifier
+                )));
+            }
+            scope.sources.push(ScopeSource {
+                offset: source.offset + offset,
+                ..source.clone()
+            });
+        }
+        scope
+            .merged
+            .extend(other.merged.iter().map(|col| MergedColumn {
+                name: col.name.clone(),
+                indices: col.indices.iter().map(|idx| idx + offset).collect(),
+            }));
+        Ok(scope)
+    }
+
+    /// 不带限定名时可见的列名，用于 `NATURAL` 连接
+    fn visible_columns(&self) -> Vec<String> {
+        self.wildcard(None)
+            .map(|columns| columns.into_iter().map(|(name, _)| name).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// 连接的中间结果
+struct Relation {
+    /// 连接结果的列和数据
+    table: Table,
+    /// 连接结果的列作用域
+    scope: Scope,
+}
+
+/// 连接类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+}
+
+/// 执行 FROM 子句中的所有连接
+///
+/// 逗号分隔的各项之间为交叉连接，每项内部的 `JOIN` 按从左到右的顺序执行。
+///
+/// # Arguments
+/// * `from` - SELECT 子句的 FROM 列表
+/// * `base` - 查询的基础求值上下文，提供表集合和外层查询的上下文
+///
+/// # Returns
+/// * `ExecutionResult<(Table, Scope)>` - 连接结果及其列作用域
+pub fn join_from(from: &[TableWithJoins], base: &EvalContext) -> ExecutionResult<(Table, Scope)> {
+    let mut relation: Option<Relation> = None;
+    for item in from {
+        let right = join_table_with_joins(item, base)?;
+        relation = Some(match relation {
+            Some(left) => join(left, right, JoinKind::Inner, &JoinConstraint::None, base)?,
+            None => right,
+        });
+    }
+    let relation = relation.ok_or(ExecutionError::ParseError(
+        "无法解析 SELECT 语句：缺少 FROM 子句".to_string(),
+    ))?;
+    Ok((relation.table, relation.scope))
+}
+
+/// 执行一个 FROM 项中的连接链
+fn join_table_with_joins(item: &TableWithJoins, base: &EvalContext) -> ExecutionResult<Relation> {
+    let mut relation = load_factor(&item.relation, base)?;
+    for Join {
+        relation: factor,
+        join_operator,
+        ..
+    } in &item.joins
+    {
+        let right = load_factor(factor, base)?;
+        let (kind, constraint) = match join_operator {
+            JoinOperator::Join(constraint) | JoinOperator::Inner(constraint) => {
+                (JoinKind::Inner, constraint)
+            }
+            JoinOperator::Left(constraint) | JoinOperator::LeftOuter(constraint) => {
+                (JoinKind::Left, constraint)
+            }
+            JoinOperator::Right(constraint) | JoinOperator::RightOuter(constraint) => {
+                (JoinKind::Right, constraint)
+            }
+            JoinOperator::FullOuter(constraint) => (JoinKind::Full, constraint),
+            JoinOperator::CrossJoin => (JoinKind::Inner, &JoinConstraint::None),
+            _ => {
+                return Err(ExecutionError::ExecutionError(format!(
+                    "不支持的连接类型: {}",
+                    factor
+                )))
+            }
+        };
+        relation = join(relation, right, kind, constraint, base)?;
+    }
+    Ok(relation)
+}
+
+/// 将 FROM 子句中的一项加载为连接的输入
+fn load_factor(factor: &TableFactor, base: &EvalContext) -> ExecutionResult<Relation> {
+    match factor {
+        TableFactor::Table { alias, .. } | TableFactor::Derived { alias, .. } => {
+            let table = load_relation(factor, base)?.into_owned();
+            let qualifier = alias
+                .as_ref()
+                .map_or(table.name.clone(), |alias| alias.name.value.clone());
+            Ok(Relation {
+                scope: Scope::from_table(&qualifier, &table),
+                table,
+            })
+        }
+        TableFactor::NestedJoin {
+            table_with_joins,
+            alias: None,
+        } => join_table_with_joins(table_with_joins, base),
+        _ => Err(ExecutionError::ExecutionError(format!(
+            "不支持的 FROM 子句: {}",
+            factor
+        ))),
+    }
+}
+
+/// 连接两个中间结果
+///
+/// 连接条件中存在左右两侧列的等值比较（包括 `USING` 和 `NATURAL`）时使用哈希连接：
+/// 以内侧的等值键建立哈希表，外侧每行只与键相同的行比较；否则使用嵌套循环。
+/// 等值键含 NULL 的行不会匹配任何行；两侧类型不同、需要隐式转换后比较的等值键不参与哈希，
+/// 而是对候选行逐一按 `=` 比较。
+///
+/// # Arguments
+/// * `left` - 左侧输入
+/// * `right` - 右侧输入
+/// * `kind` - 连接类型
+/// * `constraint` - 连接条件
+/// * `base` - 查询的基础求值上下文，连接条件中的子查询通过它执行
+fn join(
+    left: Relation,
+    right: Relation,
+    kind: JoinKind,
+    constraint: &JoinConstraint,
+    base: &EvalContext,
+) -> ExecutionResult<Relation> {
+    let left_width = left.table.columns.len();
+    let right_width = right.table.columns.len();
+    let mut scope = left.scope.concat(&right.scope, left_width)?;
+
+    let using_columns = match constraint {
+        JoinConstraint::Using(names) => names.iter().map(|name| name.to_string()).collect(),
+        JoinConstraint::Natural => {
+            let right_columns = right.scope.visible_columns();
+            left.scope
+                .visible_columns()
+                .into_iter()
+                .filter(|col| right_columns.contains(col))
+                .collect()
+        }
+        _ => vec![],
+    };
+    let (condition, keys) = match constraint {
+        JoinConstraint::On(expr) => (Some(expr), equi_join_keys(expr, &left.scope, &right.scope)),
+        _ => {
+            let mut keys = Vec::with_capacity(using_columns.len());
+            for name in &using_columns {
+                // 合并列取两侧来源列中第一个非 NULL 的值
+                let mut indices = left.scope.resolve(None, name)?;
+                indices.extend(
+                    right
+                        .scope
+                        .resolve(None, name)?
+                        .into_iter()
+                        .map(|idx| idx + left_width),
+                );
+                scope.merged.retain(|col| &col.name != name);
+                scope.merged.push(MergedColumn {
+                    name: name.clone(),
+                    indices,
+                });
+                let key = Expr::Identifier(Ident::new(name));
+                keys.push((key.clone(), key));
+            }
+            (None, keys)
+        }
+    };
+
+    // 右连接以右侧为外侧，使结果按右侧的行顺序排列
+    let (outer, inner) = match kind {
+        JoinKind::Right => (&right, &left),
+        _ => (&left, &right),
+    };
+    let (outer_keys, inner_keys): (Vec<Expr>, Vec<Expr>) = match kind {
+        JoinKind::Right => keys.into_iter().map(|(l, r)| (r, l)).unzip(),
+        _ => keys.into_iter().unzip(),
+    };
+    let combine = |outer_row: Option<&Vec<Value>>, inner_row: Option<&Vec<Value>>| {
+        let (left_row, right_row) = match kind {
+            JoinKind::Right => (inner_row, outer_row),
+            _ => (outer_row, inner_row),
+        };
+        let mut row = Vec::with_capacity(left_width + right_width);
+        match left_row {
+            Some(values) => row.extend(values.iter().cloned()),
+            None => row.resize(left_width, Value::Null),
+        }
+        match right_row {
+            Some(values) => row.extend(values.iter().cloned()),
+            None => row.resize(left_width + right_width, Value::Null),
+        }
+        row
+    };
+
+    let outer_key_rows = key_rows(outer, &outer_keys)?;
+    let inner_key_rows = key_rows(inner, &inner_keys)?;
+    let hashable = hashable_keys(&outer_key_rows, &inner_key_rows, inner_keys.len());
+    // 不参与哈希的等值键逐行按 `=` 比较；`USING` 和 `NATURAL` 没有 `ON` 条件，只能在这里检查
+    let unhashed: Vec<usize> = (0..inner_keys.len())
+        .filter(|k| !hashable.contains(k))
+        .collect();
+    let index = if hashable.is_empty() {
+        None
+    } else {
+        Some(build_hash_index(&inner_key_rows, &hashable))
+    };
+    let all_inner: Vec<usize> = (0..inner.table.data.len()).collect();
+    let combined_ctx = EvalContext {
+        scope: Some(&scope),
+        ..*base
+    };
+    let mut data = Vec::new();
+    let mut inner_matched = vec![false; inner.table.data.len()];
+    for (outer_row, outer_key) in outer.table.data.iter().zip(&outer_key_rows) {
+        let candidates: &[usize] = match &index {
+            Some(index) => match join_key(outer_key, &hashable) {
+                Some(key) => index.get(&key).map_or(&[], |rows| rows.as_slice()),
+                None => &[],
+            },
+            None => &all_inner,
+        };
+        let mut matched = false;
+        for &inner_idx in candidates {
+            if !keys_equal(outer_key, &inner_key_rows[inner_idx], &unhashed)? {
+                continue;
+            }
+            let row = combine(Some(outer_row), Some(&inner.table.data[inner_idx]));
+            if let Some(condition) = condition {
+                match ExprEvaluator::evaluate(&combined_ctx.with_row(&row), condition)? {
+                    Value::Bool(true) => {}
+                    Value::Bool(false) | Value::Null => continue,
+                    _ => {
+                        return Err(ExecutionError::ExecutionError(
+                            "连接条件必须是可判断的表达式".to_string(),
+                        ))
+                    }
+                }
+            }
+            matched = true;
+            inner_matched[inner_idx] = true;
+            data.push(row);
+        }
+        if !matched && kind != JoinKind::Inner {
+            data.push(combine(Some(outer_row), None));
+        }
+    }
+    if kind == JoinKind::Full {
+        for (inner_row, _) in inner
+            .table
+            .data
+            .iter()
+            .zip(&inner_matched)
+            .filter(|(_, &matched)| !matched)
+        {
+            data.push(combine(None, Some(inner_row)));
+        }
+    }
+
+    let mut columns = left.table.columns;
+    columns.extend(right.table.columns);
+    let mut table = Table::new(format!("{}_{}", left.table.name, right.table.name), columns);
+    table.data = data;
+    Ok(Relation { table, scope })
+}
+
+/// 计算每一行的等值键的值
+fn key_rows(relation: &Relation, keys: &[Expr]) -> ExecutionResult<Vec<Vec<Value>>> {
+    relation
+        .table
+        .data
+        .iter()
+        .map(|row| {
+            let ctx = EvalContext::default()
+                .with_scope(&relation.scope)
+                .with_row(row);
+            keys.iter()
+                .map(|expr| ExprEvaluator::evaluate(&ctx, expr))
+                .collect()
+        })
+        .collect()
+}
+
+/// 选出可以用于哈希连接的等值键的位置
+///
+/// `=` 两侧类型不同时先隐式转换再比较（如整数 `1` 等于字符串 `'1'`），按原值哈希会漏掉这些匹配，
+/// 因此只使用两侧所有非 NULL 值类型都相同的等值键，其余等值键在连接时逐行比较。
+fn hashable_keys(outer: &[Vec<Value>], inner: &[Vec<Value>], count: usize) -> Vec<usize> {
+    (0..count)
+        .filter(|&k| {
+            let mut kinds = outer
+                .iter()
+                .chain(inner)
+                .map(|key| &key[k])
+                .filter(|value| !matches!(value, Value::Null))
+                .map(std::mem::discriminant);
+            match kinds.next() {
+                Some(first) => kinds.all(|kind| kind == first),
+                None => true,
+            }
+        })
+        .collect()
+}
+
+/// 按 `=` 的语义（包括隐式类型转换）比较两行中指定位置的等值键，含 NULL 时不相等
+fn keys_equal(outer: &[Value], inner: &[Value], positions: &[usize]) -> ExecutionResult<bool> {
+    for &k in positions {
+        let equal =
+            ExprEvaluator::binary_op(&BinaryOperator::Eq, outer[k].clone(), inner[k].clone())?;
+        if equal != Value::Bool(true) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// 以内侧的等值键建立哈希表
+fn build_hash_index(
+    key_rows: &[Vec<Value>],
+    hashable: &[usize],
+) -> HashMap<Vec<Value>, Vec<usize>> {
+    let mut index: HashMap<Vec<Value>, Vec<usize>> = HashMap::new();
+    for (idx, key_row) in key_rows.iter().enumerate() {
+        if let Some(key) = join_key(key_row, hashable) {
+            index.entry(key).or_default().push(idx);
+        }
+    }
+    index
+}
+
+/// 取出一行中用于哈希的等值键，含 NULL 时返回 None
+fn join_key(key_row: &[Value], hashable: &[usize]) -> Option<Vec<Value>> {
+    let key: Vec<Value> = hashable.iter().map(|&k| key_row[k].clone()).collect();
+    if key.iter().any(|value| matches!(value, Value::Null)) {
+        return None;
+    }
+    Some(key)
+}
+
+/// 从 `ON` 条件中提取可用于哈希连接的等值键
+///
+/// 只考虑以 `AND` 连接的顶层条件中形如 `左侧表达式 = 右侧表达式` 的比较，
+/// 其中一侧只引用左侧输入的列，另一侧只引用右侧输入的列。
+/// 提取等值键后仍会对候选行求值完整的 `ON` 条件。
+///
+/// # Returns
+/// * `Vec<(Expr, Expr)>` - 每个等值键在左侧和右侧的表达式
+fn equi_join_keys(condition: &Expr, left: &Scope, right: &Scope) -> Vec<(Expr, Expr)> {
+    let mut conjuncts = vec![condition];
+    let mut keys = Vec::new();
+    while let Some(expr) = conjuncts.pop() {
+        match expr {
+            Expr::Nested(inner) => conjuncts.push(inner),
+            Expr::BinaryOp {
+                left: lhs,
+                op: BinaryOperator::And,
+                right: rhs,
+            } => {
+                conjuncts.push(rhs);
+                conjuncts.push(lhs);
+            }
+            Expr::BinaryOp {
+                left: lhs,
+                op: BinaryOperator::Eq,
+                right: rhs,
+            } => match (expr_side(lhs, left, right), expr_side(rhs, left, right)) {
+                (Some(Side::Left), Some(Side::Right)) => {
+                    keys.push((lhs.as_ref().clone(), rhs.as_ref().clone()))
+                }
+                (Some(Side::Right), Some(Side::Left)) => {
+                    keys.push((rhs.as_ref().clone(), lhs.as_ref().clone()))
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    keys
+}
+
+/// 表达式引用的列所在的一侧
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// 判断表达式是否只引用一侧输入的列
+///
+/// 不引用任何列、同时引用两侧的列或包含子查询时返回 None。
+fn expr_side(expr: &Expr, left: &Scope, right: &Scope) -> Option<Side> {
+    let mut collector = ColumnCollector::default();
+    if Visit::visit(expr, &mut collector).is_break() {
+        return None;
+    }
+    let mut side = None;
+    for (qualifier, column) in &collector.columns {
+        let in_left = left.resolve(qualifier.as_deref(), column).is_ok();
+        let in_right = right.resolve(qualifier.as_deref(), column).is_ok();
+        let column_side = match (in_left, in_right) {
+            (true, false) => Side::Left,
+            (false, true) => Side::Right,
+            _ => return None,
+        };
+        if side.is_some_and(|side| side != column_side) {
+            return None;
+        }
+        side = Some(column_side);
+    }
+    side
+}
+
+/// 收集表达式中列引用的访问器，遇到子查询时中止
+#[derive(Default)]
+struct ColumnCollector {
+    /// 列引用的限定名和列名
+    columns: Vec<(Option<String>, String)>,
+}
+
+impl Visitor for ColumnCollector {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        ControlFlow::Break(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        match expr {
+            Expr::Identifier(ident) if ident.quote_style.is_none() => {
+                self.columns.push((None, ident.value.clone()))
+            }
+            Expr::CompoundIdentifier(idents) => match idents.as_slice() {
+                [qualifier, column] => self
+                    .columns
+                    .push((Some(qualifier.value.clone()), column.value.clone())),
+                _ => return ControlFlow::Break(()),
+            },
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
@@ -8,19 +8,31 @@ use sqlparser::ast::Statement;
 use std::collections::HashMap;
 use std::sync::Mutex;
 mod create_table;
+pub(crate) mod cte;
 mod delete;
 mod drop;
 pub mod error;
+mod information_schema;
 mod insert;
-mod query;
+pub mod join;
+pub mod prepare;
+pub(crate) mod query;
 mod query_result;
+mod rename;
+mod set_operation;
+mod show;
 pub mod storage;
+pub(crate) mod subquery;
 pub mod table;
+mod truncate;
 mod update;
+mod variable;
 
 use crate::executor::table::Table;
+use crate::utils::pattern;
 
 pub use error::ExecutionError;
+pub use query_result::QueryResult;
 
 lazy_static! {
     pub static ref TABLES: Mutex<HashMap<String, Table>> = Mutex::new(HashMap::new());
@@ -40,7 +52,7 @@ lazy_static! {
 ///
 /// * `ExecutionResult<()>` - 执行结果
 pub fn execute_statement(stmt: &Statement, input: &str) -> ExecutionResult<()> {
-    *EXECUTOR_INPUT.lock().unwrap() = input.to_string();
+    begin_statement(input);
     match stmt {
         Statement::Query(_) => query::query(stmt),
         Statement::CreateTable { .. } => create_table::create_table(stmt),
@@ -48,6 +60,45 @@ pub fn execute_statement(stmt: &Statement, input: &str) -> ExecutionResult<()> {
         Statement::Insert { .. } => insert::insert(stmt),
         Statement::Delete { .. } => delete::delete(stmt),
         Statement::Update { .. } => update::update(stmt),
+        Statement::Truncate { .. } => truncate::truncate(stmt),
+        Statement::RenameTable(_) | Statement::AlterTable { .. } => rename::rename(stmt),
+        Statement::ShowTables { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowCreate { .. }
+        | Statement::ExplainTable { .. } => show::show(stmt),
+        Statement::SetVariable { .. } => variable::set_variable(stmt),
+        Statement::Prepare { .. } => prepare::prepare_sql(stmt),
+        Statement::Execute { .. } => prepare::execute_sql(stmt),
+        Statement::Deallocate { .. } => prepare::deallocate_sql(stmt),
         _ => Err(ExecutionError::ExecutionError("未识别的命令".to_string())),
     }
 }
+
+/// 执行查询语句并返回查询结果
+///
+/// 与 [`execute_statement`] 不同，该函数不输出查询结果，供嵌入使用时读取结果。
+///
+/// # Arguments
+///
+/// * `stmt` - 要执行的查询语句
+/// * `input` - 整个 SQL 语句的输入字符串，用于提取列名
+///
+/// # Returns
+///
+/// * `ExecutionResult<QueryResult>` - 查询结果，语句不是查询语句时返回错误
+pub fn query_statement(stmt: &Statement, input: &str) -> ExecutionResult<QueryResult> {
+    begin_statement(input);
+    match stmt {
+        Statement::Query(query) => query::execute_query(query),
+        _ => Err(ExecutionError::ExecutionError(
+            "只能查询 SELECT 等返回结果的语句".to_string(),
+        )),
+    }
+}
+
+/// 记录语句的输入字符串，并清空只在一条语句中有效的缓存
+fn begin_statement(input: &str) {
+    *EXECUTOR_INPUT.lock().unwrap() = input.to_string();
+    subquery::clear_cache();
+    pattern::clear_cache();
+}
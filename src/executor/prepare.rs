@@ -0,0 +1,338 @@
+/// 预处理语句模块
+///
+/// 预处理语句只解析一次，之后可以绑定不同的参数反复执行。参数以 `?`（按出现顺序编号）
+/// 或 `$1`（显式编号，从 1 开始）作为占位符，执行时占位符被替换为参数值对应的字面量，
+/// 参数值不会被当作 SQL 文本解析，因此不存在 SQL 注入的问题。
+///
+/// 除了 Rust 接口 `prepare` 之外，还支持 SQL 语句 `PREPARE`、`EXECUTE` 和 `DEALLOCATE PREPARE`。
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use sqlparser::ast::{
+    visit_expressions, visit_expressions_mut, Expr, Statement, Value as SqlValue, ValueWithSpan,
+};
+use sqlparser::tokenizer::{Location, Span};
+
+use crate::executor::{self, ExecutionError, ExecutionResult, QueryResult};
+use crate::model::convert::ToValue;
+use crate::model::Value;
+use crate::parser;
+use crate::utils::{
+    self,
+    expr_evaluator::{EvalContext, ExprEvaluator},
+};
+
+lazy_static! {
+    /// SQL 语句 `PREPARE` 创建的预处理语句，以小写的语句名为键
+    static ref PREPARED_STATEMENTS: Mutex<HashMap<String, PreparedStatement>> =
+        Mutex::new(HashMap::new());
+}
+
+/// 预处理语句
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    /// 语句的 SQL 文本，执行时用于提取列名等原始片段
+    sql: String,
+    /// 解析后的语句，其中包含参数占位符
+    statement: Statement,
+    /// 各个 `?` 占位符在 SQL 文本中的位置，按出现顺序排列
+    positional: Vec<Location>,
+    /// 参数个数
+    parameter_count: usize,
+}
+
+impl PreparedStatement {
+    /// 返回语句的参数个数
+    pub fn parameter_count(&self) -> usize {
+        self.parameter_count
+    }
+
+    /// 绑定参数并执行语句
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - 参数值，依次对应各个参数
+    ///
+    /// # Returns
+    ///
+    /// * `ExecutionResult<()>` - 执行结果，参数个数不符时返回错误
+    pub fn execute(&self, params: &[&dyn ToValue]) -> ExecutionResult<()> {
+        let statement = self.bind(params)?;
+        executor::execute_statement(&statement, &self.sql)
+    }
+
+    /// 绑定参数并执行查询，返回查询结果而不输出
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - 参数值，依次对应各个参数
+    ///
+    /// # Returns
+    ///
+    /// * `ExecutionResult<QueryResult>` - 查询结果，语句不是查询语句或参数个数不符时返回错误
+    pub fn query(&self, params: &[&dyn ToValue]) -> ExecutionResult<QueryResult> {
+        let statement = self.bind(params)?;
+        executor::query_statement(&statement, &self.sql)
+    }
+
+    /// 将占位符替换为参数值对应的字面量
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - 参数值，依次对应各个参数
+    ///
+    /// # Returns
+    ///
+    /// * `ExecutionResult<Statement>` - 绑定参数后的语句，参数个数不符或参数为无穷大、NaN 时返回错误
+    fn bind(&self, params: &[&dyn ToValue]) -> ExecutionResult<Statement> {
+        if params.len() != self.parameter_count {
+            return Err(ExecutionError::ExecutionError(format!(
+                "预处理语句需要 {} 个参数，实际为 {} 个",
+                self.parameter_count,
+                params.len()
+            )));
+        }
+        let params: Vec<Value> = params.iter().map(|param| param.to_value()).collect();
+        // SQL 中没有表示无穷大和 NaN 的字面量
+        if let Some(value) = params
+            .iter()
+            .find(|value| matches!(value, Value::Float(f) if !f.is_finite()))
+        {
+            return Err(ExecutionError::TypeUnmatch(format!(
+                "参数值 {} 不是有限的浮点数",
+                value
+            )));
+        }
+        let mut statement = self.statement.clone();
+        let _ = visit_expressions_mut(&mut statement, |expr| {
+            if let Expr::Value(ValueWithSpan {
+                value: SqlValue::Placeholder(placeholder),
+                span,
+            }) = expr
+            {
+                // 占位符在准备时已检查过，这里总能得到有效的编号
+                let index = match placeholder_number(placeholder) {
+                    Ok(Some(index)) => Some(index),
+                    Ok(None) => self.positional.binary_search(&span.start).ok(),
+                    Err(_) => None,
+                };
+                if let Some(index) = index {
+                    *expr = literal(&params[index], *span);
+                }
+            }
+            ControlFlow::<()>::Continue(())
+        });
+        Ok(statement)
+    }
+}
+
+/// 预处理一条 SQL 语句
+///
+/// # Arguments
+///
+/// * `sql` - SQL 语句字符串，必须恰好包含一条语句
+///
+/// # Returns
+///
+/// * `ExecutionResult<PreparedStatement>` - 预处理语句，解析失败或占位符无效时返回错误
+pub fn prepare(sql: &str) -> ExecutionResult<PreparedStatement> {
+    let mut statements =
+        parser::parse_sql(sql).map_err(|err| ExecutionError::ParseError(err.to_string()))?;
+    if statements.len() != 1 {
+        return Err(ExecutionError::ExecutionError(format!(
+            "预处理语句只能包含一条语句，实际为 {} 条",
+            statements.len()
+        )));
+    }
+    prepare_statement(sql.to_string(), statements.remove(0))
+}
+
+/// 由解析后的语句创建预处理语句，检查并记录其中的占位符
+fn prepare_statement(sql: String, statement: Statement) -> ExecutionResult<PreparedStatement> {
+    if matches!(
+        statement,
+        Statement::Prepare { .. } | Statement::Execute { .. } | Statement::Deallocate { .. }
+    ) {
+        return Err(ExecutionError::ExecutionError(
+            "不能预处理 PREPARE、EXECUTE 或 DEALLOCATE 语句".to_string(),
+        ));
+    }
+
+    let mut positional = Vec::new();
+    let mut max_numbered = 0;
+    let result = visit_expressions(&statement, |expr| {
+        let Expr::Value(ValueWithSpan {
+            value: SqlValue::Placeholder(placeholder),
+            span,
+        }) = expr
+        else {
+            return ControlFlow::Continue(());
+        };
+        match placeholder_number(placeholder) {
+            Ok(None) => positional.push(span.start),
+            Ok(Some(index)) => max_numbered = max_numbered.max(index + 1),
+            Err(err) => return ControlFlow::Break(err),
+        }
+        ControlFlow::Continue(())
+    });
+    if let ControlFlow::Break(err) = result {
+        return Err(err);
+    }
+    if !positional.is_empty() && max_numbered > 0 {
+        return Err(ExecutionError::ExecutionError(
+            "不能混用 ? 和 $n 两种参数占位符".to_string(),
+        ));
+    }
+
+    positional.sort();
+    Ok(PreparedStatement {
+        sql,
+        statement,
+        parameter_count: positional.len().max(max_numbered),
+        positional,
+    })
+}
+
+/// 解析占位符的编号
+///
+/// # Returns
+///
+/// * `ExecutionResult<Option<usize>>` - `$n` 占位符返回参数下标 `n - 1`，`?` 占位符返回 None，
+///   其他形式的占位符返回错误
+fn placeholder_number(placeholder: &str) -> ExecutionResult<Option<usize>> {
+    if placeholder == "?" {
+        return Ok(None);
+    }
+    match placeholder
+        .strip_prefix('$')
+        .and_then(|n| n.parse::<usize>().ok())
+    {
+        Some(n) if n >= 1 => Ok(Some(n - 1)),
+        _ => Err(ExecutionError::ExecutionError(format!(
+            "不支持的参数占位符 {}",
+            placeholder
+        ))),
+    }
+}
+
+/// 将参数值转换为 SQL 字面量，保留占位符的位置
+///
+/// 浮点数必须是有限值，由调用方预先检查。
+fn literal(value: &Value, span: Span) -> Expr {
+    let value = match value {
+        Value::Int(i) => SqlValue::Number(i.to_string(), false),
+        // 使用 Debug 格式保证浮点数带有小数点或指数，不会被当作整数解析
+        Value::Float(f) => SqlValue::Number(format!("{:?}", f), false),
+        Value::Varchar(s) => SqlValue::SingleQuotedString(s.clone()),
+        Value::Bool(b) => SqlValue::Boolean(*b),
+        Value::Null => SqlValue::Null,
+    };
+    Expr::Value(ValueWithSpan { value, span })
+}
+
+/// 执行 `PREPARE name FROM 'sql'` 或 `PREPARE name AS sql`
+///
+/// 同名的预处理语句已存在时被替换。
+///
+/// # Arguments
+///
+/// * `stmt` - SQL 语句对象，预期为 PREPARE 语句
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 执行结果
+pub fn prepare_sql(stmt: &Statement) -> ExecutionResult<()> {
+    let Statement::Prepare {
+        name, statement, ..
+    } = stmt
+    else {
+        return Err(ExecutionError::ParseError(
+            "无法解析 PREPARE 语句".to_string(),
+        ));
+    };
+    // 语句的位置信息相对于原始输入，这里以语句的规范文本重新解析，使执行时能够提取原始片段
+    let prepared = prepare(&statement.to_string())?;
+    utils::log_info(format!(
+        "PREPARE: 语句 {} 已准备，共 {} 个参数",
+        name.value,
+        prepared.parameter_count()
+    ));
+    PREPARED_STATEMENTS
+        .lock()
+        .unwrap()
+        .insert(name.value.to_lowercase(), prepared);
+    Ok(())
+}
+
+/// 执行 `EXECUTE name USING v1, v2` 或 `EXECUTE name(v1, v2)`
+///
+/// # Arguments
+///
+/// * `stmt` - SQL 语句对象，预期为 EXECUTE 语句
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 执行结果，预处理语句不存在或参数个数不符时返回错误
+pub fn execute_sql(stmt: &Statement) -> ExecutionResult<()> {
+    let Statement::Execute {
+        name: Some(name),
+        parameters,
+        using,
+        immediate: false,
+        ..
+    } = stmt
+    else {
+        return Err(ExecutionError::ExecutionError(
+            "不支持的 EXECUTE 语句".to_string(),
+        ));
+    };
+    let key = name.to_string().to_lowercase();
+    // 先取出预处理语句再执行，执行过程中可能再次访问预处理语句表
+    let prepared = PREPARED_STATEMENTS
+        .lock()
+        .unwrap()
+        .get(&key)
+        .cloned()
+        .ok_or_else(|| ExecutionError::ExecutionError(format!("预处理语句 {} 不存在", name)))?;
+    let ctx = EvalContext::default();
+    let params = parameters
+        .iter()
+        .chain(using.iter().map(|param| &param.expr))
+        .map(|expr| ExprEvaluator::evaluate(&ctx, expr))
+        .collect::<ExecutionResult<Vec<Value>>>()?;
+    let params: Vec<&dyn ToValue> = params.iter().map(|param| param as &dyn ToValue).collect();
+    prepared.execute(&params)
+}
+
+/// 执行 `DEALLOCATE PREPARE name`
+///
+/// # Arguments
+///
+/// * `stmt` - SQL 语句对象，预期为 DEALLOCATE 语句
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 执行结果，预处理语句不存在时返回错误
+pub fn deallocate_sql(stmt: &Statement) -> ExecutionResult<()> {
+    let Statement::Deallocate { name, .. } = stmt else {
+        return Err(ExecutionError::ParseError(
+            "无法解析 DEALLOCATE 语句".to_string(),
+        ));
+    };
+    match PREPARED_STATEMENTS
+        .lock()
+        .unwrap()
+        .remove(&name.value.to_lowercase())
+    {
+        Some(_) => {
+            utils::log_info(format!("DEALLOCATE: 语句 {} 已释放", name.value));
+            Ok(())
+        }
+        None => Err(ExecutionError::ExecutionError(format!(
+            "预处理语句 {} 不存在",
+            name.value
+        ))),
+    }
+}
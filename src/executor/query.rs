@@ -1,11 +1,16 @@
+use std::borrow::Cow;
+
 use crate::executor::query_result::QueryResult;
+use crate::executor::table::Table;
 use crate::executor::ExecutionResult;
-use sqlparser::ast::{SetExpr, Statement};
+use crate::utils::expr_evaluator::EvalContext;
+use crate::utils::query_processor::RowLimit;
+use sqlparser::ast::{OrderBy, Query, Select, SetExpr, Statement, TableFactor};
 
-use super::{ExecutionError, TABLES};
+use super::{cte, information_schema, join, set_operation, ExecutionError, TABLES};
 
 /// 执行查询语句
-/// 处理 SQL 查询语句并输出结果。支持标准 SELECT 查询，包含表查询和无表查询。
+/// 处理 SQL 查询语句并输出结果。支持标准 SELECT 查询，包含单表查询、多表连接查询和无表查询。
 ///
 /// # Arguments
 /// * `stmt` - SQL 语句，表示要执行的查询
@@ -19,76 +24,259 @@ use super::{ExecutionError, TABLES};
 ///
 pub fn query(stmt: &Statement) -> ExecutionResult<()> {
     match stmt {
-        Statement::Query(query) => match &*query.body {
-            SetExpr::Select(select) => {
-                // 处理无表查询，比如 SELECT 1+1
-                if select.from.is_empty() {
-                    let query_result = QueryResult::from_table(
-                        None,
-                        &select.selection,
-                        &select.projection,
-                        &query.order_by,
-                    )?;
-                    println!("{}", query_result.display());
-                    return Ok(());
-                }
-
-                // 处理有表的查询
-                let table_name = extract_table_name(&select.from[0].relation)?;
-                let tables = TABLES.lock().unwrap();
-                let table = tables.get(table_name);
-                if table.is_none() {
-                    return Err(ExecutionError::TableNotFound(table_name.to_string()));
-                }
-                let query_result = QueryResult::from_table(
-                    table,
-                    &select.selection,
-                    &select.projection,
-                    &query.order_by,
-                )?;
-                println!("{}", query_result.display());
-                Ok(())
-            }
-            _ => Err(ExecutionError::ParseError(
-                "无法解析查询语句：不支持的查询类型".to_string(),
-            )),
-        },
+        Statement::Query(query) => {
+            let query_result = execute_query(query)?;
+            println!("{}", query_result.display());
+            Ok(())
+        }
         _ => Err(ExecutionError::ParseError(
             "无法解析查询语句：不是查询语句".to_string(),
         )),
     }
 }
 
+/// 执行查询并返回查询结果
+///
+/// 与 [`query`] 不同，该函数不输出结果，而是将其作为 `QueryResult` 返回，
+/// 供 `CREATE TABLE ... AS SELECT` 等需要复用查询结果的语句使用。
+///
+/// # Arguments
+/// * `query` - 查询语句的 AST
+///
+/// # Returns
+/// * `ExecutionResult<QueryResult>` - 查询结果
+///
+/// # Errors
+/// * `ExecutionError::ParseError` - 如果查询类型不受支持
+/// * `ExecutionError::TableNotFound` - 如果查询的表不存在
+pub fn execute_query(query: &Query) -> ExecutionResult<QueryResult> {
+    let tables = TABLES.lock().unwrap();
+    execute_query_with(query, &EvalContext::default().with_catalog(&tables))
+}
+
+/// 在给定的执行环境中执行查询
+///
+/// 子查询、派生表和公共表表达式也通过该函数执行，避免重复获取表集合的锁。
+/// 查询带有 `WITH` 子句时，先物化其中的公共表表达式，再执行查询主体。
+/// 集合操作的 `ORDER BY` 和 `LIMIT` 作用于合并后的结果。
+///
+/// # Arguments
+/// * `query` - 查询语句的 AST
+/// * `env` - 执行环境，提供表集合、可见的公共表表达式，以及子查询的外层查询上下文
+///
+/// # Returns
+/// * `ExecutionResult<QueryResult>` - 查询结果
+pub(crate) fn execute_query_with(query: &Query, env: &EvalContext) -> ExecutionResult<QueryResult> {
+    let ctes;
+    let env = match &query.with {
+        Some(with) => {
+            ctes = cte::materialize(with, env)?;
+            env.with_ctes(&ctes)
+        }
+        None => *env,
+    };
+    match &*query.body {
+        SetExpr::Select(select) => {
+            execute_select(select, &query.order_by, RowLimit::from_query(query)?, &env)
+        }
+        body => execute_set_expr(body, &env)?
+            .order_and_limit(&query.order_by, RowLimit::from_query(query)?),
+    }
+}
+
+/// 执行不带排序和行数限制的查询主体
+///
+/// 用于执行集合操作的两侧、递归公共表表达式的初始部分和递归部分等没有外层 `ORDER BY`/`LIMIT` 的查询主体。
+/// 集合操作（`UNION`、`INTERSECT`、`EXCEPT`）先分别执行两侧，再合并结果。
+///
+/// # Arguments
+/// * `body` - 查询主体
+/// * `env` - 执行环境
+///
+/// # Returns
+/// * `ExecutionResult<QueryResult>` - 查询结果
+pub(crate) fn execute_set_expr(body: &SetExpr, env: &EvalContext) -> ExecutionResult<QueryResult> {
+    match body {
+        SetExpr::Select(select) => execute_select(select, &None, RowLimit::default(), env),
+        SetExpr::Query(query) => execute_query_with(query, env),
+        SetExpr::SetOperation {
+            op,
+            set_quantifier,
+            left,
+            right,
+        } => set_operation::combine(
+            execute_set_expr(left, env)?,
+            execute_set_expr(right, env)?,
+            op,
+            set_quantifier,
+        ),
+        _ => Err(ExecutionError::ParseError(
+            "无法解析查询语句：不支持的查询类型".to_string(),
+        )),
+    }
+}
+
+/// 执行 SELECT 查询
+///
+/// # Arguments
+/// * `select` - SELECT 子句
+/// * `order_by` - 排序条件
+/// * `row_limit` - 行数限制
+/// * `env` - 执行环境
+///
+/// # Returns
+/// * `ExecutionResult<QueryResult>` - 查询结果
+fn execute_select(
+    select: &Select,
+    order_by: &Option<OrderBy>,
+    row_limit: RowLimit,
+    env: &EvalContext,
+) -> ExecutionResult<QueryResult> {
+    let base = EvalContext {
+        catalog: env.catalog,
+        ctes: env.ctes,
+        outer: env.outer,
+        ..Default::default()
+    };
+
+    // 处理无表查询，比如 SELECT 1+1
+    if select.from.is_empty() {
+        return QueryResult::from_table(base, select, order_by, row_limit);
+    }
+
+    // 处理多表查询：先执行连接，再在连接结果上查询
+    if select.from.len() > 1 || !select.from[0].joins.is_empty() {
+        let (joined, scope) = join::join_from(&select.from, &base)?;
+        return QueryResult::from_table(
+            EvalContext {
+                table: Some(&joined),
+                scope: Some(&scope),
+                ..base
+            },
+            select,
+            order_by,
+            row_limit,
+        );
+    }
+
+    // 处理单表查询
+    let relation = &select.from[0].relation;
+    let table = load_relation(relation, &base)?;
+    QueryResult::from_table(
+        EvalContext {
+            table: Some(&table),
+            alias: extract_table_alias(relation),
+            ..base
+        },
+        select,
+        order_by,
+        row_limit,
+    )
+}
+
+/// 加载 FROM 子句中引用的表
+///
+/// 派生表（`FROM (SELECT ...) AS t`）先执行其子查询并转换为临时表；
+/// 表名优先解析为可见的公共表表达式，其次是 `information_schema` 中按当前表定义即时生成的虚拟表，
+/// 其余的表直接从表集合中借用。
+///
+/// # Arguments
+/// * `relation` - FROM 子句中的表引用
+/// * `env` - 执行环境，提供表集合和可见的公共表表达式
+///
+/// # Returns
+/// * `ExecutionResult<Cow<Table>>` - 表对象
+///
+/// # Errors
+/// * `ExecutionError::ParseError` - 如果无法解析表名
+/// * `ExecutionError::TableNotFound` - 如果表不存在
+pub(super) fn load_relation<'a>(
+    relation: &TableFactor,
+    env: &EvalContext<'a>,
+) -> ExecutionResult<Cow<'a, Table>> {
+    if let TableFactor::Derived {
+        lateral,
+        subquery,
+        alias,
+    } = relation
+    {
+        if *lateral {
+            return Err(ExecutionError::ExecutionError(
+                "不支持 LATERAL 派生表".to_string(),
+            ));
+        }
+        let alias = alias.as_ref().ok_or(ExecutionError::ExecutionError(
+            "派生表必须指定别名".to_string(),
+        ))?;
+        return Ok(Cow::Owned(
+            execute_query_with(subquery, env)?.into_table(alias)?,
+        ));
+    }
+
+    let table_name = extract_table_name(relation)?;
+    if let Some(table) = env.ctes.and_then(|ctes| ctes.get(&table_name)) {
+        return Ok(Cow::Borrowed(table));
+    }
+    let tables = env.catalog.ok_or(ExecutionError::ExecutionError(
+        "无法在没有表集合的环境下加载表".to_string(),
+    ))?;
+    if information_schema::is_virtual_table(&table_name) {
+        return Ok(Cow::Owned(information_schema::build_virtual_table(
+            &table_name,
+            tables,
+        )?));
+    }
+    match tables.get(&table_name) {
+        Some(table) => Ok(Cow::Borrowed(table)),
+        None => Err(ExecutionError::TableNotFound(table_name)),
+    }
+}
+
 /// 从 SQL 表达式中提取表名
 ///
 /// 从给定的 TableFactor 对象中提取出表名字符串，主要用于 SELECT 查询中的 FROM 子句处理。
+/// 带模式名的表名（如 `information_schema.tables`）各部分以 `.` 连接。
 ///
 /// # Arguments
 ///
 /// * `relation` - 表达式，代表 SQL 查询中的表引用
 ///
 /// # Returns
-/// * `Result<&String, ExecutionError>` - 成功时返回表名的引用，失败时返回错误
+/// * `Result<String, ExecutionError>` - 成功时返回表名，失败时返回错误
 ///
 /// # Errors
 /// * `ExecutionError::ParseError` - 如果无法从表达式中解析出有效的表名
-/// * `ExecutionError::TableNotFound` - 如果解析出的表名在数据库中不存在
-fn extract_table_name(relation: &sqlparser::ast::TableFactor) -> Result<&String, ExecutionError> {
-    let table_name = match relation {
-        sqlparser::ast::TableFactor::Table { name, .. } => {
-            if let Some(ident) = name.0.first() {
-                &ident.as_ident().unwrap().value
-            } else {
-                return Err(ExecutionError::ParseError(
-                    "无法解析 SELECT 语句：无法提取表名".to_string(),
-                ));
-            }
-        }
-        _ => {
-            return Err(ExecutionError::ParseError(
-                "无法解析 SELECT 语句：无法提取表名".to_string(),
-            ));
+fn extract_table_name(relation: &TableFactor) -> Result<String, ExecutionError> {
+    let parse_error =
+        || ExecutionError::ParseError("无法解析 SELECT 语句：无法提取表名".to_string());
+    match relation {
+        TableFactor::Table { name, .. } if !name.0.is_empty() => name
+            .0
+            .iter()
+            .map(|part| part.as_ident().map(|ident| ident.value.clone()))
+            .collect::<Option<Vec<String>>>()
+            .map(|parts| parts.join("."))
+            .ok_or_else(parse_error),
+        _ => Err(parse_error()),
+    }
+}
+
+/// 从 SQL 表达式中提取表别名
+///
+/// # Arguments
+///
+/// * `relation` - 表达式，代表 SQL 查询中的表引用
+///
+/// # Returns
+/// * `Option<&str>` - `FROM orders o` 或 `FROM orders AS o` 中的别名，没有别名时返回 None
+fn extract_table_alias(relation: &TableFactor) -> Option<&str> {
+    match relation {
+        TableFactor::Table {
+            alias: Some(alias), ..
         }
-    };
-    Ok(table_name)
+        | TableFactor::Derived {
+            alias: Some(alias), ..
+        } => Some(&alias.name.value),
+        _ => None,
+    }
 }
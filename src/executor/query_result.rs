@@ -1,12 +1,21 @@
 /// 查询结果模块
 ///
 /// 定义了查询结果的数据结构和格式化方法，用于存储和展示 SQL 查询的结果。
-use sqlparser::ast::{Expr, OrderBy, SelectItem};
+use std::collections::HashSet;
 
-use crate::executor::table::Table;
-use crate::model::Value;
-use crate::utils::expr_evaluator::ExprEvaluator;
-use crate::utils::query_processor::QueryProcessor;
+use sqlparser::ast::{
+    Distinct, Expr, Ident, OrderBy, OrderByKind, Select, SelectItem, TableAlias, Value as SqlValue,
+    ValueWithSpan,
+};
+
+use super::table::Table;
+use super::{ExecutionError, ExecutionResult};
+use crate::model::{Column, ColumnDataType, Value};
+use crate::utils::aggregate;
+use crate::utils::expr_evaluator::{EvalContext, ExprEvaluator};
+use crate::utils::grouping::{self, GroupBy};
+use crate::utils::query_processor::{QueryProcessor, RowLimit};
+use crate::utils::window::{self, WindowValues};
 
 /// 查询结果结构
 ///
@@ -36,55 +45,392 @@ impl QueryResult {
 
     /// 从表对象创建查询结果
     ///
-    /// 根据指定的过滤条件、列投影和排序条件从表中提取数据，构建查询结果。
+    /// 根据 SELECT 子句中的过滤条件、分组、列投影和 `DISTINCT`，以及排序条件和行数限制从表中提取数据，构建查询结果。
     /// 如果表为 None，则处理不涉及表的查询（如直接 SELECT 表达式）。
+    /// 带 `GROUP BY`，或者投影、`HAVING`、`ORDER BY` 中包含聚合函数时，按分组聚合，每个分组产生一行结果。
+    /// 投影和 `ORDER BY` 中的窗口函数在过滤之后计算，每一行得到一个值。
     ///
     /// # Arguments
     ///
-    /// * `table` - 可选的表对象，查询的数据源
-    /// * `where_clause` - 可选的 WHERE 过滤条件
-    /// * `column_projection` - 列投影定义，指定要返回哪些列
-    /// * `order_by_clause` - 可选的排序条件
+    /// * `source` - 查询数据源的求值上下文，包含可选的表对象，以及单表查询的表别名或多表连接的列作用域
+    /// * `select` - SELECT 子句，提供 WHERE 过滤条件、分组、`HAVING`、列投影和 `DISTINCT` 标记
+    /// * `order_by_clause` - 可选的排序条件，可以引用列别名
+    /// * `row_limit` - 排序后应用的 `LIMIT`/`OFFSET`
     ///
     /// # Returns
     ///
     /// * `Result<QueryResult, ExecutionError>` - 生成的查询结果或错误
     pub fn from_table(
-        table: Option<&Table>,
-        where_clause: &Option<Expr>,
-        column_projection: &[SelectItem],
+        source: EvalContext,
+        select: &Select,
         order_by_clause: &Option<OrderBy>,
+        row_limit: RowLimit,
     ) -> Result<Self, super::ExecutionError> {
-        let columns = QueryProcessor::extract_columns_name(table, column_projection)?;
-        match table {
-            Some(table) => {
-                let filter_indices = table.filter_rows(where_clause)?;
-                let sorted_indices = QueryProcessor::sort_rows_by_order(table, order_by_clause)?;
-                let rows = QueryProcessor::extract_rows(
-                    table,
-                    &sorted_indices,
-                    &filter_indices,
-                    column_projection,
-                )?;
-                Ok(Self::new(columns, rows))
+        let where_clause = &select.selection;
+        let column_projection = &select.projection;
+        let is_distinct = match &select.distinct {
+            None => false,
+            Some(Distinct::Distinct) => true,
+            Some(Distinct::On(_)) => {
+                return Err(super::ExecutionError::ExecutionError(
+                    "不支持 DISTINCT ON".to_string(),
+                ))
             }
+        };
+        let ctx = source;
+        let table = ctx.table;
+        let columns = QueryProcessor::extract_columns_name(&ctx, column_projection)?;
+        let group_by = GroupBy::new(&select.group_by, column_projection, table)?;
+        let order_by_clause =
+            QueryProcessor::resolve_order_by_aliases(&ctx, order_by_clause, column_projection)?;
+        let having = select
+            .having
+            .as_ref()
+            .map(|expr| grouping::resolve_aliases(expr, column_projection));
+        let is_grouped = !group_by.exprs.is_empty()
+            || QueryProcessor::is_aggregate_query(column_projection)
+            || having.as_ref().is_some_and(aggregate::contains_aggregate)
+            || QueryProcessor::order_by_exprs(&order_by_clause)
+                .iter()
+                .any(|expr| aggregate::contains_aggregate(expr));
+
+        let window_exprs = window::collect_window_functions(
+            column_projection
+                .iter()
+                .filter_map(|item| match item {
+                    SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                        Some(expr)
+                    }
+                    _ => None,
+                })
+                .chain(QueryProcessor::order_by_exprs(&order_by_clause)),
+        );
+        if !window_exprs.is_empty() && (is_grouped || table.is_none()) {
+            return Err(super::ExecutionError::ExecutionError(
+                "窗口函数不能用于分组查询或无表查询".to_string(),
+            ));
+        }
+
+        let mut filter_indices = match table {
+            Some(table) => table.filter_rows_in(&ctx, where_clause)?,
             None => {
+                // 无表查询视为只有一个没有列的虚拟行
                 let should_return_row = match where_clause.as_ref() {
-                    Some(expr) => matches!(
-                        ExprEvaluator::evaluate_expr(None, expr, None),
-                        Ok(Value::Bool(true))
-                    ),
+                    Some(expr) => {
+                        matches!(ExprEvaluator::evaluate(&ctx, expr), Ok(Value::Bool(true)))
+                    }
                     None => true,
                 };
                 if should_return_row {
-                    let rows: Vec<Value> =
-                        QueryProcessor::process_projection(None, None, column_projection)?;
-                    Ok(Self::new(columns, vec![rows]))
+                    vec![0]
                 } else {
-                    Ok(Self::new(columns, vec![]))
+                    vec![]
+                }
+            }
+        };
+
+        if is_grouped {
+            let rows = Self::aggregate_groups(
+                &ctx,
+                &group_by,
+                &filter_indices,
+                select,
+                having.as_ref(),
+                &order_by_clause,
+                row_limit,
+                is_distinct,
+            )?;
+            return Ok(Self::new(columns, rows));
+        }
+
+        if let Some(having) = &having {
+            // 没有分组时，HAVING 对每一行生效
+            let mut kept = Vec::with_capacity(filter_indices.len());
+            for idx in filter_indices {
+                let row_ctx = match table {
+                    Some(table) => ctx.with_row(&table.data[idx]),
+                    None => ctx,
+                };
+                if ExprEvaluator::evaluate(&row_ctx, having)? == Value::Bool(true) {
+                    kept.push(idx);
                 }
             }
+            filter_indices = kept;
+        }
+
+        // 窗口函数在过滤之后、排序和投影之前计算
+        let windows;
+        let ctx = if window_exprs.is_empty() {
+            ctx
+        } else {
+            windows = WindowValues::compute(&ctx, &filter_indices, window_exprs)?;
+            ctx.with_windows(&windows)
+        };
+
+        match table {
+            Some(_) => {
+                // 去重会改变行数，此时需要完整排序，并在去重后再应用 LIMIT/OFFSET
+                let top_k = if is_distinct { None } else { row_limit.top_k() };
+                let sorted_indices = QueryProcessor::sort_rows_by_order(
+                    &ctx,
+                    &filter_indices,
+                    &order_by_clause,
+                    top_k,
+                )?;
+                let rows = if is_distinct {
+                    let rows =
+                        QueryProcessor::extract_rows(&ctx, &sorted_indices, column_projection)?;
+                    row_limit.apply(QueryProcessor::deduplicate_rows(rows))
+                } else {
+                    QueryProcessor::extract_rows(
+                        &ctx,
+                        &row_limit.apply(sorted_indices),
+                        column_projection,
+                    )?
+                };
+                Ok(Self::new(columns, rows))
+            }
+            None if filter_indices.is_empty() => Ok(Self::new(columns, vec![])),
+            None => {
+                let row = QueryProcessor::process_projection(&ctx, column_projection)?;
+                Ok(Self::new(columns, row_limit.apply(vec![row])))
+            }
+        }
+    }
+
+    /// 按分组聚合过滤后的行
+    ///
+    /// 先检查投影、`HAVING` 和 `ORDER BY` 只引用了分组表达式和聚合函数，
+    /// 然后对每个分组求值 `HAVING` 条件，对保留的分组排序并应用 `DISTINCT` 和 `LIMIT`/`OFFSET`，
+    /// 最后对每个分组求值列投影得到一行结果。
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - 查询的求值上下文
+    /// * `group_by` - 查询的分组方式
+    /// * `indices` - 过滤后的行索引
+    /// * `select` - SELECT 子句，提供列投影
+    /// * `having` - 替换列别名后的 `HAVING` 条件
+    /// * `order_by_clause` - 替换列别名后的排序条件
+    /// * `row_limit` - 排序后应用的 `LIMIT`/`OFFSET`
+    /// * `is_distinct` - 是否对结果行去重
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<Value>>, ExecutionError>` - 结果行或错误
+    #[allow(clippy::too_many_arguments)]
+    fn aggregate_groups(
+        ctx: &EvalContext,
+        group_by: &GroupBy,
+        indices: &[usize],
+        select: &Select,
+        having: Option<&Expr>,
+        order_by_clause: &Option<OrderBy>,
+        row_limit: RowLimit,
+        is_distinct: bool,
+    ) -> Result<Vec<Vec<Value>>, super::ExecutionError> {
+        let column_projection = &select.projection;
+        for item in column_projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    group_by.validate_expr(ctx, expr)?
+                }
+                SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => {
+                    for column in ctx.table.iter().flat_map(|table| &table.columns) {
+                        group_by.validate_expr(ctx, &Expr::Identifier(Ident::new(&column.name)))?;
+                    }
+                }
+            }
+        }
+        if let Some(having) = having {
+            group_by.validate_expr(ctx, having)?;
+        }
+        for expr in QueryProcessor::order_by_exprs(order_by_clause) {
+            group_by.validate_expr(ctx, expr)?;
+        }
+
+        let groups = group_by.group_rows(ctx, indices)?;
+        let mut positions = Vec::with_capacity(groups.len());
+        for (pos, group) in groups.iter().enumerate() {
+            let keep = match having {
+                Some(having) => {
+                    ExprEvaluator::evaluate(&group.context(ctx, group_by), having)?
+                        == Value::Bool(true)
+                }
+                None => true,
+            };
+            if keep {
+                positions.push(pos);
+            }
+        }
+
+        let top_k = if is_distinct { None } else { row_limit.top_k() };
+        let sorted_positions = QueryProcessor::sort_groups_by_order(
+            ctx,
+            &groups,
+            group_by,
+            &positions,
+            order_by_clause,
+            top_k,
+        )?;
+        let project = |positions: &[usize]| {
+            positions
+                .iter()
+                .map(|&pos| {
+                    QueryProcessor::process_projection(
+                        &groups[pos].context(ctx, group_by),
+                        column_projection,
+                    )
+                })
+                .collect::<Result<Vec<Vec<Value>>, super::ExecutionError>>()
+        };
+        if is_distinct {
+            Ok(row_limit.apply(QueryProcessor::deduplicate_rows(project(
+                &sorted_positions,
+            )?)))
+        } else {
+            project(&row_limit.apply(sorted_positions))
+        }
+    }
+
+    /// 对查询结果排序并应用行数限制
+    ///
+    /// 用于集合操作外层的 `ORDER BY` 和 `LIMIT`。排序表达式中的列名按结果的列名解析，
+    /// 整数常量（如 `ORDER BY 2`）表示按结果中的第几列排序。
+    ///
+    /// # Arguments
+    ///
+    /// * `order_by_clause` - 可选的排序条件
+    /// * `row_limit` - 行数限制
+    ///
+    /// # Returns
+    ///
+    /// * `ExecutionResult<QueryResult>` - 排序并截取后的查询结果
+    pub fn order_and_limit(
+        self,
+        order_by_clause: &Option<OrderBy>,
+        row_limit: RowLimit,
+    ) -> ExecutionResult<Self> {
+        let mut order_by = order_by_clause.clone();
+        if let Some(OrderByKind::Expressions(exprs)) =
+            order_by.as_mut().map(|order_by| &mut order_by.kind)
+        {
+            for order_expr in exprs {
+                if let Expr::Value(ValueWithSpan {
+                    value: SqlValue::Number(n, _),
+                    ..
+                }) = &order_expr.expr
+                {
+                    let name = n
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|pos| pos.checked_sub(1))
+                        .and_then(|pos| self.columns.get(pos))
+                        .ok_or(ExecutionError::ExecutionError(format!(
+                            "ORDER BY 中的列位置 {} 超出范围",
+                            n
+                        )))?;
+                    order_expr.expr = Expr::Identifier(Ident::new(name));
+                }
+            }
+        }
+
+        let mut table = Table::new(String::new(), self.derive_columns());
+        table.data = self.rows;
+        let indices: Vec<usize> = (0..table.data.len()).collect();
+        let sorted = QueryProcessor::sort_rows_by_order(
+            &EvalContext::new(Some(&table), None),
+            &indices,
+            &order_by,
+            row_limit.top_k(),
+        )?;
+        let rows = row_limit
+            .apply(sorted)
+            .into_iter()
+            .map(|idx| std::mem::take(&mut table.data[idx]))
+            .collect();
+        Ok(Self::new(self.columns, rows))
+    }
+
+    /// 从查询结果推导列定义
+    ///
+    /// 某列的非 NULL 值全部为整数或布尔值时推导为 `INT`，有整数超出 `INT` 范围时推导为 `BIGINT`，
+    /// 否则推导为 `VARCHAR`。
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Column>` - 推导出的列定义
+    pub fn derive_columns(&self) -> Vec<Column> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let mut values = self
+                    .rows
+                    .iter()
+                    .map(|row| &row[i])
+                    .filter(|value| !matches!(value, Value::Null))
+                    .peekable();
+                let mut data_type = values.peek().map(|_| ColumnDataType::Int(None));
+                for value in values {
+                    data_type = match (data_type, value) {
+                        (Some(ColumnDataType::Int(_)), Value::Int(i))
+                            if i32::try_from(*i).is_err() =>
+                        {
+                            Some(ColumnDataType::BigInt(None))
+                        }
+                        (data_type, Value::Int(_) | Value::Bool(_)) => data_type,
+                        _ => None,
+                    };
+                }
+                Column {
+                    name: name.clone(),
+                    data_type: data_type.unwrap_or(ColumnDataType::Varchar(None)),
+                    is_primary_key: false,
+                    is_nullable: true,
+                }
+            })
+            .collect()
+    }
+
+    /// 将查询结果转换为临时表，供派生表和公共表表达式使用
+    ///
+    /// 表名取自别名；别名带有列名列表（如 `AS t(a, b)`）时用它替换结果的列名。
+    /// 与 `CREATE TABLE ... AS SELECT` 不同，行数据原样保留，不做类型校验。
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - 派生表或公共表表达式的名称及可选的列名列表
+    ///
+    /// # Returns
+    ///
+    /// * `ExecutionResult<Table>` - 临时表；列名列表长度与结果列数不一致或列名重复时返回错误
+    pub fn into_table(mut self, alias: &TableAlias) -> ExecutionResult<Table> {
+        if !alias.columns.is_empty() {
+            if alias.columns.len() != self.columns.len() {
+                return Err(ExecutionError::ExecutionError(format!(
+                    "'{}' 的列名数量 ({}) 与查询结果列数 ({}) 不匹配",
+                    alias.name.value,
+                    alias.columns.len(),
+                    self.columns.len()
+                )));
+            }
+            self.columns = alias
+                .columns
+                .iter()
+                .map(|column| column.name.value.clone())
+                .collect();
+        }
+        let mut seen = HashSet::new();
+        if let Some(duplicate) = self.columns.iter().find(|name| !seen.insert(*name)) {
+            return Err(ExecutionError::ExecutionError(format!(
+                "'{}' 中存在重复的列名 '{}'",
+                alias.name.value, duplicate
+            )));
         }
+        let mut table = Table::new(alias.name.value.clone(), self.derive_columns());
+        table.data = self.rows;
+        Ok(table)
     }
 
     /// 格式化查询结果为表格字符串
@@ -107,7 +453,7 @@ impl QueryResult {
 
         // 考虑列标题的宽度
         for (i, col) in self.columns.iter().enumerate() {
-            column_widths[i] = col.len().max(3); // 至少3个字符宽度
+            column_widths[i] = col.chars().count().max(3); // 至少3个字符宽度
         }
 
         // 考虑数据行的宽度
@@ -118,7 +464,7 @@ impl QueryResult {
                         Value::Null => String::new(), // NULL值显示为空字符串
                         _ => value.to_string(),
                     };
-                    column_widths[i] = column_widths[i].max(value_str.len());
+                    column_widths[i] = column_widths[i].max(value_str.chars().count());
                 }
             }
         }
@@ -0,0 +1,106 @@
+/// 表重命名操作模块
+///
+/// 实现 `RENAME TABLE a TO b` 以及 `ALTER TABLE a RENAME TO b` 语句的解析和执行逻辑，
+/// 负责同时修改内存中的表名和磁盘上的表文件。
+use std::collections::{HashMap, HashSet};
+
+use crate::executor::information_schema::ensure_writable;
+use crate::executor::storage::rename_table_file;
+use crate::executor::table::Table;
+use crate::executor::{ExecutionError, ExecutionResult, TABLES};
+use crate::utils;
+use sqlparser::ast::{AlterTableOperation, Statement};
+
+/// 执行表重命名操作
+///
+/// 解析 RENAME TABLE 或 ALTER TABLE ... RENAME TO 语句，按顺序重命名各表。
+/// 执行前会先检查所有重命名操作，只要有一项失败（原表不存在或新表名已被占用），
+/// 所有表都保持不变。执行中重命名表文件失败时，撤销已完成的重命名后返回错误。
+///
+/// # Arguments
+///
+/// * `stmt` - SQL 语句对象，预期为 RENAME TABLE 或 ALTER TABLE 语句
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 重命名的结果，成功或失败
+pub fn rename(stmt: &Statement) -> ExecutionResult<()> {
+    let renames: Vec<(String, String)> = match stmt {
+        Statement::RenameTable(rename_tables) => rename_tables
+            .iter()
+            .map(|r| (r.old_name.to_string(), r.new_name.to_string()))
+            .collect(),
+        Statement::AlterTable {
+            name, operations, ..
+        } => operations
+            .iter()
+            .map(|operation| match operation {
+                AlterTableOperation::RenameTable { table_name } => {
+                    Ok((name.to_string(), table_name.to_string()))
+                }
+                _ => Err(ExecutionError::ExecutionError(format!(
+                    "暂不支持的 ALTER TABLE 操作: {}",
+                    operation
+                ))),
+            })
+            .collect::<ExecutionResult<_>>()?,
+        _ => {
+            return Err(ExecutionError::ParseError(
+                "无法解析 RENAME TABLE 语句".to_string(),
+            ))
+        }
+    };
+
+    for (old_name, new_name) in &renames {
+        ensure_writable(old_name)?;
+        ensure_writable(new_name)?;
+    }
+
+    let mut tables = TABLES
+        .lock()
+        .map_err(|e| ExecutionError::ExecutionError(format!("锁定TABLES失败: {}", e)))?;
+
+    // 先按顺序模拟所有重命名，确保整体可以成功执行
+    let mut table_names: HashSet<String> = tables.keys().cloned().collect();
+    for (old_name, new_name) in &renames {
+        if !table_names.remove(old_name) {
+            return Err(ExecutionError::TableNotFound(old_name.clone()));
+        }
+        if !table_names.insert(new_name.clone()) {
+            return Err(ExecutionError::TableExists(new_name.clone()));
+        }
+    }
+
+    // 先重命名表文件，成功后再修改内存中的表
+    for (applied, (old_name, new_name)) in renames.iter().enumerate() {
+        if let Err(err) = rename_table_file(old_name, new_name) {
+            for (old_name, new_name) in renames[..applied].iter().rev() {
+                // 尽力恢复表文件，内存中的表总能恢复
+                if let Err(err) = rename_table_file(new_name, old_name) {
+                    utils::log_warning(format!(
+                        "无法将表文件 {} 恢复为 {}: {}",
+                        new_name, old_name, err
+                    ));
+                }
+                rename_in_memory(&mut tables, new_name, old_name);
+            }
+            return Err(err);
+        }
+        rename_in_memory(&mut tables, old_name, new_name);
+    }
+    for (old_name, new_name) in &renames {
+        utils::log_info(format!(
+            "RENAME: 成功将表 {} 重命名为 {}",
+            old_name, new_name
+        ));
+    }
+    Ok(())
+}
+
+/// 修改内存中的表名，调用前已检查原表存在且新表名未被占用
+fn rename_in_memory(tables: &mut HashMap<String, Table>, old_name: &str, new_name: &str) {
+    if let Some(mut table) = tables.remove(old_name) {
+        table.name = new_name.to_string();
+        tables.insert(new_name.to_string(), table);
+    }
+}
@@ -0,0 +1,142 @@
+/// 集合操作模块
+///
+/// 实现 `UNION`、`INTERSECT` 和 `EXCEPT` 及其 `ALL` 形式，将两个查询结果合并为一个。
+/// 两侧的列数必须相同，对应列的类型必须兼容，结果的列名取自左侧。
+/// 比较行时 NULL 与 NULL 视为相同的值。
+use std::collections::HashMap;
+
+use sqlparser::ast::{SetOperator, SetQuantifier};
+
+use super::query_result::QueryResult;
+use super::{ExecutionError, ExecutionResult};
+use crate::model::Value;
+use crate::utils::query_processor::QueryProcessor;
+
+/// 合并集合操作两侧的查询结果
+///
+/// 不带 `ALL` 的形式对结果去重；`INTERSECT ALL` 和 `EXCEPT ALL` 按多重集合语义计算，
+/// 即一行在结果中出现的次数分别为其在两侧出现次数的较小值和差值。
+///
+/// # Arguments
+/// * `left` - 左侧查询结果
+/// * `right` - 右侧查询结果
+/// * `op` - 集合操作类型
+/// * `quantifier` - `ALL` 或 `DISTINCT` 修饰
+///
+/// # Returns
+/// * `ExecutionResult<QueryResult>` - 合并后的查询结果
+pub fn combine(
+    left: QueryResult,
+    right: QueryResult,
+    op: &SetOperator,
+    quantifier: &SetQuantifier,
+) -> ExecutionResult<QueryResult> {
+    let is_all = match quantifier {
+        SetQuantifier::All => true,
+        SetQuantifier::Distinct | SetQuantifier::None => false,
+        _ => {
+            return Err(ExecutionError::ExecutionError(format!(
+                "不支持的集合操作修饰: {}",
+                quantifier
+            )));
+        }
+    };
+    check_compatible(&left, &right, op)?;
+
+    let QueryResult { columns, rows } = left;
+    let rows = match op {
+        SetOperator::Union => {
+            let mut rows = rows;
+            rows.extend(right.rows);
+            rows
+        }
+        SetOperator::Intersect => {
+            let mut counts = count_rows(right.rows);
+            rows.into_iter()
+                .filter(|row| take_one(&mut counts, row, is_all))
+                .collect()
+        }
+        SetOperator::Except | SetOperator::Minus => {
+            let mut counts = count_rows(right.rows);
+            rows.into_iter()
+                .filter(|row| !take_one(&mut counts, row, is_all))
+                .collect()
+        }
+    };
+    let rows = if is_all {
+        rows
+    } else {
+        QueryProcessor::deduplicate_rows(rows)
+    };
+    Ok(QueryResult::new(columns, rows))
+}
+
+/// 检查集合操作两侧的列数和列类型是否兼容
+///
+/// 某列在两侧都有非 NULL 值时，一侧为字符串而另一侧为数值（整数、浮点数或布尔值）视为不兼容。
+fn check_compatible(
+    left: &QueryResult,
+    right: &QueryResult,
+    op: &SetOperator,
+) -> ExecutionResult<()> {
+    if left.columns.len() != right.columns.len() {
+        return Err(ExecutionError::ExecutionError(format!(
+            "{} 两侧的列数不一致：左侧 {} 列，右侧 {} 列",
+            op,
+            left.columns.len(),
+            right.columns.len()
+        )));
+    }
+    for (i, name) in left.columns.iter().enumerate() {
+        if let (Some(left_is_string), Some(right_is_string)) =
+            (is_string_column(left, i), is_string_column(right, i))
+        {
+            if left_is_string != right_is_string {
+                let describe = |is_string| if is_string { "字符串" } else { "数值" };
+                return Err(ExecutionError::ExecutionError(format!(
+                    "{} 两侧第 {} 列 '{}' 的类型不兼容：左侧为{}，右侧为{}",
+                    op,
+                    i + 1,
+                    name,
+                    describe(left_is_string),
+                    describe(right_is_string)
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 判断结果中的某列是否为字符串列，以第一个非 NULL 值为准；全为 NULL 时返回 None
+fn is_string_column(result: &QueryResult, column: usize) -> Option<bool> {
+    result
+        .rows
+        .iter()
+        .map(|row| &row[column])
+        .find(|value| !matches!(value, Value::Null))
+        .map(|value| matches!(value, Value::Varchar(_)))
+}
+
+/// 统计每一行出现的次数
+fn count_rows(rows: Vec<Vec<Value>>) -> HashMap<Vec<Value>, usize> {
+    let mut counts = HashMap::with_capacity(rows.len());
+    for row in rows {
+        *counts.entry(row).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// 判断一行是否在另一侧出现
+///
+/// `ALL` 形式下每次匹配会消耗另一侧的一次出现，从而实现多重集合语义。
+fn take_one(counts: &mut HashMap<Vec<Value>, usize>, row: &[Value], is_all: bool) -> bool {
+    match counts.get_mut(row) {
+        Some(count) if *count > 0 => {
+            if is_all {
+                *count -= 1;
+            }
+            true
+        }
+        _ => false,
+    }
+}
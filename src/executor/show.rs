@@ -0,0 +1,160 @@
+/// 元数据查看模块
+///
+/// 实现 `SHOW TABLES`、`DESCRIBE t` / `SHOW COLUMNS FROM t` 以及 `SHOW CREATE TABLE t`
+/// 语句，以查询结果的形式展示数据库中的表及其结构。
+use crate::executor::query_result::QueryResult;
+use crate::executor::table::Table;
+use crate::executor::{ExecutionError, ExecutionResult, TABLES};
+use crate::model::Value;
+use sqlparser::ast::{ObjectName, ShowCreateObject, ShowStatementOptions, Statement};
+
+/// 执行元数据查看语句
+///
+/// 根据语句类型生成对应的查询结果并输出。
+///
+/// # Arguments
+///
+/// * `stmt` - SQL 语句对象，预期为 SHOW 或 DESCRIBE 语句
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 执行结果，成功或失败
+pub fn show(stmt: &Statement) -> ExecutionResult<()> {
+    let query_result = match stmt {
+        Statement::ShowTables { show_options, .. } => {
+            reject_filter(show_options)?;
+            show_tables()
+        }
+        Statement::ExplainTable { table_name, .. } => describe(table_name)?,
+        Statement::ShowColumns { show_options, .. } => {
+            reject_filter(show_options)?;
+            let table_name = show_options
+                .show_in
+                .as_ref()
+                .and_then(|show_in| show_in.parent_name.as_ref())
+                .ok_or(ExecutionError::ParseError(
+                    "无法解析 SHOW COLUMNS 语句：缺少表名".to_string(),
+                ))?;
+            describe(table_name)?
+        }
+        Statement::ShowCreate {
+            obj_type: ShowCreateObject::Table,
+            obj_name,
+        } => show_create_table(obj_name)?,
+        _ => {
+            return Err(ExecutionError::ExecutionError(
+                "暂不支持的 SHOW 语句".to_string(),
+            ))
+        }
+    };
+    println!("{}", query_result.display());
+    Ok(())
+}
+
+/// 列出所有表
+///
+/// # Returns
+///
+/// * `QueryResult` - 单列 `Tables` 的查询结果，按表名排序
+fn show_tables() -> QueryResult {
+    let tables = TABLES.lock().unwrap();
+    let mut table_names: Vec<&String> = tables.keys().collect();
+    table_names.sort();
+    let rows = table_names
+        .into_iter()
+        .map(|name| vec![Value::Varchar(name.clone())])
+        .collect();
+    QueryResult::new(vec!["Tables".to_string()], rows)
+}
+
+/// 查看表的列定义
+///
+/// 每列输出一行，包含列名、类型、是否可为 NULL、键类型和默认值。
+///
+/// # Arguments
+///
+/// * `table_name` - 表名
+///
+/// # Returns
+///
+/// * `ExecutionResult<QueryResult>` - 列定义组成的查询结果
+fn describe(table_name: &ObjectName) -> ExecutionResult<QueryResult> {
+    with_table(table_name, |table| {
+        let rows = table
+            .columns
+            .iter()
+            .map(|col| {
+                let is_nullable = col.is_nullable && !col.is_primary_key;
+                vec![
+                    Value::Varchar(col.name.clone()),
+                    Value::Varchar(col.data_type.to_string()),
+                    Value::Varchar(if is_nullable { "YES" } else { "NO" }.to_string()),
+                    Value::Varchar(if col.is_primary_key { "PRI" } else { "" }.to_string()),
+                    Value::Null,
+                ]
+            })
+            .collect();
+        QueryResult::new(
+            ["Field", "Type", "Null", "Key", "Default"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            rows,
+        )
+    })
+}
+
+/// 查看表的建表语句
+///
+/// # Arguments
+///
+/// * `table_name` - 表名
+///
+/// # Returns
+///
+/// * `ExecutionResult<QueryResult>` - 包含表名和建表语句的查询结果
+fn show_create_table(table_name: &ObjectName) -> ExecutionResult<QueryResult> {
+    with_table(table_name, |table| {
+        QueryResult::new(
+            vec!["Table".to_string(), "Create Table".to_string()],
+            vec![vec![
+                Value::Varchar(table.name.clone()),
+                Value::Varchar(table.create_statement()),
+            ]],
+        )
+    })
+}
+
+/// 查找表并对其执行操作
+///
+/// # Arguments
+///
+/// * `table_name` - 表名
+/// * `f` - 对表执行的操作
+///
+/// # Returns
+///
+/// * `ExecutionResult<QueryResult>` - 操作的结果，表不存在时返回 `TableNotFound`
+fn with_table(
+    table_name: &ObjectName,
+    f: impl FnOnce(&Table) -> QueryResult,
+) -> ExecutionResult<QueryResult> {
+    let table_name = table_name.to_string();
+    let tables = TABLES.lock().unwrap();
+    match tables.get(&table_name) {
+        Some(table) => Ok(f(table)),
+        None => Err(ExecutionError::TableNotFound(table_name)),
+    }
+}
+
+/// 拒绝带过滤条件的 SHOW 语句
+///
+/// `LIKE` / `WHERE` 过滤条件暂不支持，直接返回错误而不是忽略条件。
+fn reject_filter(show_options: &ShowStatementOptions) -> ExecutionResult<()> {
+    match &show_options.filter_position {
+        Some(_) => Err(ExecutionError::ExecutionError(
+            "暂不支持 SHOW 语句的过滤条件".to_string(),
+        )),
+        None => Ok(()),
+    }
+}
@@ -3,6 +3,7 @@
 /// 提供数据库表的加载和保存功能，支持将表结构和内容序列化到磁盘文件，
 /// 以及从磁盘文件反序列化表数据。
 use crate::executor::error::{ExecutionError, ExecutionResult};
+use crate::executor::table::Table;
 use crate::executor::TABLES;
 use crate::utils;
 use bincode::config;
@@ -107,3 +108,57 @@ pub fn remove_table_file(table_name: &str) -> ExecutionResult<()> {
         },
     }
 }
+
+/// 重写已保存的表文件
+///
+/// 若表文件已存在于磁盘上，则用当前表内容覆盖它；
+/// 若表尚未保存过，则不做任何操作，等待下次保存时统一写入。
+///
+/// # Arguments
+///
+/// * `table` - 要写入的表
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 写入结果
+pub fn rewrite_table_file(table: &Table) -> ExecutionResult<()> {
+    let file_path = format!("data/{}.{}", table.name, FILE_EXTENSION);
+    if !Path::new(&file_path).exists() {
+        return Ok(());
+    }
+
+    let mut file = File::create(&file_path)
+        .map_err(|e| ExecutionError::FileError(format!("创建文件 {:?} 失败: {}", file_path, e)))?;
+    bincode::encode_into_std_write(table, &mut file, config::standard())
+        .map_err(|e| ExecutionError::SerializationError(table.name.clone(), e.to_string()))?;
+    Ok(())
+}
+
+/// 重命名表文件
+///
+/// 将磁盘上的表文件从旧表名重命名为新表名。
+/// 若旧表文件不存在（表于本次会话中创建，尚未保存），则不做任何操作。
+///
+/// # Arguments
+///
+/// * `old_name` - 原表名
+/// * `new_name` - 新表名
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 重命名结果
+pub fn rename_table_file(old_name: &str, new_name: &str) -> ExecutionResult<()> {
+    let old_path = format!("data/{}.{}", old_name, FILE_EXTENSION);
+    let new_path = format!("data/{}.{}", new_name, FILE_EXTENSION);
+
+    match std::fs::rename(&old_path, &new_path) {
+        Ok(_) => Ok(()),
+        Err(err) => match err.kind() {
+            ErrorKind::NotFound => Ok(()),
+            _ => Err(ExecutionError::FileError(format!(
+                "重命名表文件错误: {}",
+                err
+            ))),
+        },
+    }
+}
@@ -0,0 +1,78 @@
+/// 子查询模块
+///
+/// 负责在表达式求值过程中执行子查询。子查询通过求值上下文访问已加锁的表集合，
+/// 并把外层查询的上下文作为外层作用域，以支持引用外层列的相关子查询。
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use sqlparser::ast::Query;
+
+use super::query::execute_query_with;
+use super::query_result::QueryResult;
+use super::{ExecutionError, ExecutionResult};
+use crate::utils::expr_evaluator::EvalContext;
+
+/// 子查询的缓存状态
+enum CachedSubquery {
+    /// 不引用外层列的子查询，结果与外层的当前行无关
+    Uncorrelated(Arc<QueryResult>),
+    /// 引用了外层列的子查询，需要对外层的每一行重新执行
+    Correlated,
+}
+
+lazy_static! {
+    /// 当前语句中子查询的缓存，以子查询 AST 节点的地址为键
+    static ref SUBQUERY_CACHE: Mutex<HashMap<usize, CachedSubquery>> = Mutex::new(HashMap::new());
+}
+
+/// 清空子查询缓存
+///
+/// 每条语句执行前调用，缓存只在一条语句的执行过程中有效。
+pub fn clear_cache() {
+    SUBQUERY_CACHE.lock().unwrap().clear();
+}
+
+/// 在给定上下文中执行子查询
+///
+/// 子查询第一次执行时不提供外层作用域：执行成功说明它没有引用外层列，
+/// 结果会被缓存并在同一条语句中复用；否则将其视为相关子查询，
+/// 之后对外层的每一行都带着外层作用域重新执行。
+///
+/// # Arguments
+/// * `ctx` - 外层表达式的求值上下文，必须包含表集合；其中可见的公共表表达式在子查询中同样可见
+/// * `query` - 子查询
+///
+/// # Returns
+/// * `ExecutionResult<Arc<QueryResult>>` - 子查询的结果
+pub fn execute_subquery(ctx: &EvalContext, query: &Query) -> ExecutionResult<Arc<QueryResult>> {
+    let env = EvalContext {
+        catalog: Some(ctx.catalog.ok_or(ExecutionError::ExecutionError(
+            "此处不支持子查询".to_string(),
+        ))?),
+        ctes: ctx.ctes,
+        ..Default::default()
+    };
+    let key = query as *const Query as usize;
+    let is_correlated = match SUBQUERY_CACHE.lock().unwrap().get(&key) {
+        Some(CachedSubquery::Uncorrelated(result)) => return Ok(Arc::clone(result)),
+        Some(CachedSubquery::Correlated) => true,
+        None => false,
+    };
+
+    if !is_correlated {
+        if let Ok(result) = execute_query_with(query, &env) {
+            let result = Arc::new(result);
+            SUBQUERY_CACHE
+                .lock()
+                .unwrap()
+                .insert(key, CachedSubquery::Uncorrelated(Arc::clone(&result)));
+            return Ok(result);
+        }
+        SUBQUERY_CACHE
+            .lock()
+            .unwrap()
+            .insert(key, CachedSubquery::Correlated);
+    }
+    execute_query_with(query, &env.with_outer(Some(ctx))).map(Arc::new)
+}
@@ -4,7 +4,8 @@
 use bincode::{Decode, Encode};
 
 use crate::model::{Column, ColumnDataType, Value};
-use crate::utils::expr_evaluator::ExprEvaluator;
+use crate::utils::coercion;
+use crate::utils::expr_evaluator::{EvalContext, ExprEvaluator};
 use crate::utils::log_info;
 
 use super::ExecutionError;
@@ -14,7 +15,7 @@ use sqlparser::ast::{Assignment, AssignmentTarget, Expr};
 ///
 /// 存储表的元数据（表名和列定义）以及实际的行数据。
 /// 支持序列化和反序列化以实现持久化存储。
-#[derive(Debug, Encode, Decode)]
+#[derive(Debug, Clone, Encode, Decode)]
 pub struct Table {
     /// 表名
     pub name: String,
@@ -45,7 +46,7 @@ impl Table {
 
     /// 插入一行数据
     ///
-    /// 在插入前会验证数据是否符合表的约束条件。
+    /// 在插入前会将数据隐式转换为各列的类型，并验证数据是否符合表的约束条件。
     ///
     /// # Arguments
     ///
@@ -56,17 +57,42 @@ impl Table {
     /// * `Ok(())` 插入成功
     /// * `Err(ExecutionError)` 插入失败，包含详细错误信息
     pub fn insert_row(&mut self, values: Vec<Value>) -> Result<(), ExecutionError> {
+        let values = self.coerce_row(values)?;
         self.validate_row(&values)?;
         self.data.push(values);
         Ok(())
     }
 
+    /// 将行数据隐式转换为各列的类型
+    ///
+    /// 例如向整数列写入 `'42'` 时转换为整数 42，具体规则见 [`coercion::coerce_for_column`]。
+    /// 值的数量与列数不一致时保持原样，交给 [`Table::validate_row`] 报告错误。
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - 要写入的一行的值
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Value>, ExecutionError>` - 转换后的值，严格模式下转换会丢失信息时返回错误
+    fn coerce_row(&self, values: Vec<Value>) -> Result<Vec<Value>, ExecutionError> {
+        if values.len() != self.columns.len() {
+            return Ok(values);
+        }
+        values
+            .into_iter()
+            .zip(&self.columns)
+            .map(|(value, column)| coercion::coerce_for_column(value, &column.data_type))
+            .collect()
+    }
+
     /// 验证行数据是否符合表的约束
     ///
     /// 进行的验证包括：
     /// - 检查值的数量是否与列数匹配
     /// - 验证每个值的类型是否与对应列的类型匹配
     /// - 确保整数和字符串值不超过其定义的长度限制
+    /// - 确保整数值在列类型的取值范围之内
     /// - 防止在非空或主键列中插入 NULL 值
     /// - 确保主键不重复
     ///
@@ -90,15 +116,25 @@ impl Table {
         for (i, value) in values.iter().enumerate() {
             let column = &self.columns[i];
             match (value, &column.data_type) {
-                (Value::Int(val), ColumnDataType::Int(Some(max_len))) => {
-                    if val.to_string().len() > *max_len as usize {
-                        return Err(ExecutionError::TypeUnmatch(format!(
-                            "列 '{}' 的整数值 {} 超出长度限制 {}",
-                            column.name, val, max_len
-                        )));
+                (Value::Int(val), data_type) if data_type.is_integer() => {
+                    if let Some(max_len) = data_type.length() {
+                        if val.to_string().len() > max_len as usize {
+                            return Err(ExecutionError::TypeUnmatch(format!(
+                                "列 '{}' 的整数值 {} 超出长度限制 {}",
+                                column.name, val, max_len
+                            )));
+                        }
+                    }
+                    if let Some((min, max)) = data_type.integer_range() {
+                        if !(min..=max).contains(val) {
+                            println!("Error: Out of range value for column '{}'", column.name);
+                            return Err(ExecutionError::TypeUnmatch(format!(
+                                "列 '{}' 的整数值 {} 超出 {} 的范围 [{}, {}]",
+                                column.name, val, data_type, min, max
+                            )));
+                        }
                     }
                 }
-                (Value::Int(_), ColumnDataType::Int(_)) => {}
                 (Value::Varchar(val), ColumnDataType::Varchar(Some(max_len))) => {
                     if val.len() > *max_len as usize {
                         return Err(ExecutionError::TypeUnmatch(format!(
@@ -142,6 +178,14 @@ impl Table {
         Ok(())
     }
 
+    /// 清空表中的所有行
+    ///
+    /// 保留表结构和约束，仅移除数据。表目前不维护自增计数等额外状态，
+    /// 因此清空数据后即回到新建时的状态。
+    pub fn truncate(&mut self) {
+        self.data.clear();
+    }
+
     /// 删除满足条件的行
     ///
     /// # Arguments
@@ -154,17 +198,24 @@ impl Table {
     /// * `Err(ExecutionError)` 删除失败
     pub fn delete_rows(&mut self, where_clause: &Option<Expr>) -> Result<(), ExecutionError> {
         let matching_row_indices = self.filter_rows(where_clause)?;
+        self.delete_row_indices(matching_row_indices);
+        Ok(())
+    }
 
+    /// 删除指定索引的行
+    ///
+    /// # Arguments
+    ///
+    /// * `row_indices` - 要删除的行索引，通常由 [`Table::filter_rows_in`] 得到
+    pub fn delete_row_indices(&mut self, row_indices: Vec<usize>) {
         // 从后向前删除，避免索引错位
-        let mut matching_row_indices = matching_row_indices;
+        let mut matching_row_indices = row_indices;
         matching_row_indices.sort_unstable_by(|a, b| b.cmp(a));
 
         for idx in matching_row_indices.iter() {
             log_info(format!("Delete Row {:?}", self.data[*idx]).to_string());
             self.data.remove(*idx);
         }
-
-        Ok(())
     }
 
     /// 过滤满足条件的行
@@ -179,16 +230,41 @@ impl Table {
     ///
     /// * `Result<Vec<usize>, ExecutionError>` - 满足条件的行索引列表
     pub fn filter_rows(&self, where_clause: &Option<Expr>) -> Result<Vec<usize>, ExecutionError> {
+        self.filter_rows_in(&EvalContext::default(), where_clause)
+    }
+
+    /// 在给定上下文中过滤满足条件的行
+    ///
+    /// 与 [`Table::filter_rows`] 相同，但会沿用上下文中的表别名等信息。
+    /// 上下文中的表和行会被替换为本表及其每一行。
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - 求值上下文
+    /// * `where_clause` - 可选的 WHERE 条件表达式
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<usize>, ExecutionError>` - 满足条件的行索引列表
+    pub fn filter_rows_in(
+        &self,
+        ctx: &EvalContext,
+        where_clause: &Option<Expr>,
+    ) -> Result<Vec<usize>, ExecutionError> {
         if where_clause.is_none() {
             // 如果没有 WHERE 子句，返回所有行的索引
             return Ok((0..self.data.len()).collect());
         }
         let expr = where_clause.as_ref().unwrap();
         let mut matching_rows = Vec::new();
+        let ctx = EvalContext {
+            table: Some(self),
+            ..*ctx
+        };
 
         // 遍历所有行，评估 WHERE 表达式
         for (row_idx, row) in self.data.iter().enumerate() {
-            match ExprEvaluator::evaluate_expr(Some(self), expr, Some(row)) {
+            match ExprEvaluator::evaluate(&ctx.with_row(row), expr) {
                 Ok(Value::Bool(true)) => matching_rows.push(row_idx),
                 Ok(Value::Bool(false)) => {}
                 Ok(Value::Null) => {}
@@ -218,15 +294,38 @@ impl Table {
         assignments: &Vec<Assignment>,
         where_clause: &Option<Expr>,
     ) -> Result<(), ExecutionError> {
-        let matching_row_indices = self.filter_rows(where_clause)?;
-        if matching_row_indices.is_empty() {
-            return Ok(());
-        }
+        let updates = self.evaluate_updates(&EvalContext::default(), assignments, where_clause)?;
+        self.apply_updates(updates)
+    }
 
-        for row_idx in matching_row_indices {
-            let mut row = self.data[row_idx].clone();
-            let original_row = row.clone();
-            self.data.remove(row_idx);
+    /// 计算满足条件的行更新后的值
+    ///
+    /// 所有赋值表达式都基于行更新前的值求值，此时表不会被修改，
+    /// 因此赋值表达式和 WHERE 条件中的子查询可以读取任意表（包括本表）。
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - 求值上下文，提供子查询所需的表集合
+    /// * `assignments` - 列赋值表达式列表
+    /// * `where_clause` - 可选的 WHERE 条件表达式
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(usize, Vec<Value>)>, ExecutionError>` - 每个要更新的行索引及其新值
+    pub fn evaluate_updates(
+        &self,
+        ctx: &EvalContext,
+        assignments: &Vec<Assignment>,
+        where_clause: &Option<Expr>,
+    ) -> Result<Vec<(usize, Vec<Value>)>, ExecutionError> {
+        let ctx = EvalContext {
+            table: Some(self),
+            ..*ctx
+        };
+        let mut updates = Vec::new();
+        for row_idx in self.filter_rows_in(&ctx, where_clause)? {
+            let original_row = &self.data[row_idx];
+            let mut row = original_row.clone();
             for assignment in assignments {
                 let column_name = match &assignment.target {
                     AssignmentTarget::ColumnName(name) => name.to_string(),
@@ -236,12 +335,9 @@ impl Table {
                 };
                 let column_index = self.get_column_index(&column_name);
                 if let Some(index) = column_index {
-                    let value = ExprEvaluator::evaluate_expr(
-                        Some(self),
-                        &assignment.value,
-                        Some(&original_row),
-                    )?;
-                    row[index] = value.clone();
+                    let value =
+                        ExprEvaluator::evaluate(&ctx.with_row(original_row), &assignment.value)?;
+                    row[index] = value;
                 } else {
                     return Err(ExecutionError::ExecutionError(format!(
                         "列 '{}' 在表 '{}' 中不存在",
@@ -249,6 +345,29 @@ impl Table {
                     )));
                 }
             }
+            updates.push((row_idx, row));
+        }
+        Ok(updates)
+    }
+
+    /// 应用 [`Table::evaluate_updates`] 计算出的更新
+    ///
+    /// 每一行在替换前都会验证约束，验证失败时该行保持不变并返回错误。
+    ///
+    /// # Arguments
+    ///
+    /// * `updates` - 每个要更新的行索引及其新值
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ExecutionError>` - 更新成功或失败
+    pub fn apply_updates(
+        &mut self,
+        updates: Vec<(usize, Vec<Value>)>,
+    ) -> Result<(), ExecutionError> {
+        for (row_idx, row) in updates {
+            let row = self.coerce_row(row)?;
+            let original_row = self.data.remove(row_idx);
             match self.validate_row(&row) {
                 Ok(_) => self.data.insert(row_idx, row),
                 Err(e) => {
@@ -261,6 +380,25 @@ impl Table {
         Ok(())
     }
 
+    /// 生成建表语句
+    ///
+    /// 根据表的列定义和约束重新生成等价的 `CREATE TABLE` 语句。
+    ///
+    /// # Returns
+    ///
+    /// * `String` - 可直接执行的建表语句
+    pub fn create_statement(&self) -> String {
+        format!(
+            "CREATE TABLE {} ({})",
+            self.name,
+            self.columns
+                .iter()
+                .map(|col| col.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+
     /// 获取列索引
     ///
     /// 根据列名查找其在表中的索引位置。
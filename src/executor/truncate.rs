@@ -0,0 +1,45 @@
+/// 清空表操作模块
+///
+/// 实现 TRUNCATE TABLE 语句的解析和执行逻辑，负责快速清空表中的所有数据。
+use crate::executor::information_schema::ensure_writable;
+use crate::executor::storage::rewrite_table_file;
+use crate::executor::{ExecutionError, ExecutionResult, TABLES};
+use crate::utils;
+use sqlparser::ast::Statement;
+
+/// 执行清空表操作
+///
+/// 解析 TRUNCATE 语句，验证表是否存在，然后清空表中的所有行，
+/// 并重写磁盘上已保存的表文件。表结构和约束保持不变。
+///
+/// # Arguments
+///
+/// * `stmt` - SQL 语句对象，预期为 TRUNCATE 语句
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 清空表的结果，成功或失败
+pub fn truncate(stmt: &Statement) -> ExecutionResult<()> {
+    if let Statement::Truncate { table_names, .. } = stmt {
+        let mut tables = TABLES
+            .lock()
+            .map_err(|e| ExecutionError::ExecutionError(format!("锁定TABLES失败: {}", e)))?;
+
+        for target in table_names {
+            let table_name = target.name.to_string();
+            ensure_writable(&table_name)?;
+            let table = tables
+                .get_mut(&table_name)
+                .ok_or(ExecutionError::TableNotFound(table_name.clone()))?;
+
+            table.truncate();
+            rewrite_table_file(table)?;
+            utils::log_info(format!("TRUNCATE: 成功清空表 {}", table_name));
+        }
+        Ok(())
+    } else {
+        Err(ExecutionError::ParseError(
+            "无法解析 TRUNCATE 语句".to_string(),
+        ))
+    }
+}
@@ -1,7 +1,9 @@
 /// 更新操作模块
 ///
 /// 实现 UPDATE 语句的解析和执行逻辑，负责更新表中的数据。
+use crate::executor::information_schema::ensure_writable;
 use crate::executor::{ExecutionError, ExecutionResult, TABLES};
+use crate::utils::expr_evaluator::EvalContext;
 use sqlparser::ast::{Statement, TableFactor};
 
 /// 执行更新操作
@@ -31,14 +33,20 @@ pub fn update(stmt: &Statement) -> ExecutionResult<()> {
                 ));
             }
         };
+        ensure_writable(&table_name)?;
         let mut tables = TABLES.lock().unwrap();
-        if tables.contains_key(&table_name) == false {
-            return Err(ExecutionError::TableNotFound(table_name));
-        }
-        let table = tables.get_mut(&table_name).unwrap();
+        let table = tables
+            .get(&table_name)
+            .ok_or(ExecutionError::TableNotFound(table_name.clone()))?;
         let where_clause = selection;
 
-        table.update_rows(assignments, where_clause)?;
+        // 先基于更新前的数据计算新值，赋值和条件中的子查询可以读取所有表
+        let ctx = EvalContext::default().with_catalog(&tables);
+        let updates = table.evaluate_updates(&ctx, assignments, where_clause)?;
+        tables
+            .get_mut(&table_name)
+            .unwrap()
+            .apply_updates(updates)?;
     } else {
         return Err(ExecutionError::ParseError("无法解析UPDATE语句".to_string()));
     }
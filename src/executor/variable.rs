@@ -0,0 +1,66 @@
+/// 系统变量模块
+///
+/// 实现 `SET` 语句，目前支持通过 `sql_mode` 开启或关闭严格模式。
+use std::sync::atomic::Ordering;
+
+use sqlparser::ast::{Expr, Statement, Value as SqlValue};
+
+use crate::executor::{ExecutionError, ExecutionResult};
+use crate::utils::{self, IS_STRICT_MODE};
+
+/// 使 `sql_mode` 启用严格模式的模式名
+const STRICT_MODES: [&str; 2] = ["STRICT_TRANS_TABLES", "STRICT_ALL_TABLES"];
+
+/// 执行 `SET` 语句
+///
+/// `SET sql_mode = '...'` 中包含 `STRICT_TRANS_TABLES` 或 `STRICT_ALL_TABLES` 时启用严格模式，
+/// 否则关闭严格模式；其余模式名被忽略。
+///
+/// # Arguments
+///
+/// * `stmt` - SQL 语句对象，预期为 SET 语句
+///
+/// # Returns
+///
+/// * `ExecutionResult<()>` - 执行结果，变量不受支持时返回错误
+pub fn set_variable(stmt: &Statement) -> ExecutionResult<()> {
+    let Statement::SetVariable {
+        variables, value, ..
+    } = stmt
+    else {
+        return Err(ExecutionError::ParseError("无法解析 SET 语句".to_string()));
+    };
+    let name = variables.to_string();
+    let name = name.trim_start_matches("@@").to_lowercase();
+    if name != "sql_mode" {
+        return Err(ExecutionError::ExecutionError(format!(
+            "不支持的系统变量 {}",
+            name
+        )));
+    }
+    let mode = match value.as_slice() {
+        [Expr::Value(value)] => match &value.value {
+            SqlValue::SingleQuotedString(s) | SqlValue::DoubleQuotedString(s) => s.clone(),
+            _ => {
+                return Err(ExecutionError::ExecutionError(format!(
+                    "sql_mode 的值必须是字符串，实际为 {}",
+                    value
+                )))
+            }
+        },
+        _ => {
+            return Err(ExecutionError::ExecutionError(
+                "sql_mode 的值必须是字符串".to_string(),
+            ))
+        }
+    };
+    let is_strict = mode
+        .split(',')
+        .any(|mode| STRICT_MODES.contains(&mode.trim().to_uppercase().as_str()));
+    IS_STRICT_MODE.store(is_strict, Ordering::Relaxed);
+    utils::log_info(format!(
+        "SET: 严格模式已{}",
+        if is_strict { "启用" } else { "关闭" }
+    ));
+    Ok(())
+}
@@ -6,12 +6,15 @@ use std::sync::atomic::Ordering;
 
 use utils::IS_INFO_OUTPUT;
 
+pub mod database;
 pub mod executor;
 pub mod model;
 pub mod parser;
 pub mod repl;
 pub mod utils;
 
+pub use database::Database;
+
 /// 执行 SQL 语句
 ///
 /// 这是一个用于外部测试的函数，它会关闭信息输出，执行 SQL 语句，
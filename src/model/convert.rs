@@ -0,0 +1,232 @@
+/// 类型转换模块
+///
+/// 在 `Value` 与 Rust 类型之间转换：`FromValue` 将查询结果中的值转换为 Rust 类型，
+/// `ToValue` 将 Rust 类型转换为绑定到预处理语句的参数值，`FromRow` 将查询结果的一行转换为结构体。
+/// 结构体可以通过 `#[derive(FromRow)]` 按字段名从同名列中取值。
+use crate::executor::ExecutionError;
+use crate::model::Value;
+
+pub use simple_db_derive::FromRow;
+
+/// 从 `Value` 转换得到的类型
+pub trait FromValue: Sized {
+    /// 转换一个值
+    ///
+    /// # Arguments
+    /// * `value` - 查询结果中的值
+    ///
+    /// # Returns
+    /// * `Result<Self, ExecutionError>` - 转换结果，值的类型不符或为 NULL 时返回错误
+    fn from_value(value: &Value) -> Result<Self, ExecutionError>;
+}
+
+/// 可以转换为 `Value` 的类型，用于绑定预处理语句的参数
+pub trait ToValue {
+    /// 转换为值
+    fn to_value(&self) -> Value;
+}
+
+/// 可以由查询结果的一行构造的类型
+pub trait FromRow: Sized {
+    /// 由一行构造
+    ///
+    /// # Arguments
+    /// * `row` - 查询结果的一行
+    ///
+    /// # Returns
+    /// * `Result<Self, ExecutionError>` - 构造结果，缺少列或值的类型不符时返回错误
+    fn from_row(row: &Row) -> Result<Self, ExecutionError>;
+}
+
+/// 查询结果的一行，可以按列名或位置取值
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'a> {
+    /// 结果集的列名
+    columns: &'a [String],
+    /// 当前行的值，与列名一一对应
+    values: &'a [Value],
+}
+
+impl<'a> Row<'a> {
+    /// 创建一行
+    ///
+    /// # Arguments
+    /// * `columns` - 结果集的列名
+    /// * `values` - 当前行的值
+    pub fn new(columns: &'a [String], values: &'a [Value]) -> Self {
+        Self { columns, values }
+    }
+
+    /// 返回当前行的值
+    pub fn values(&self) -> &'a [Value] {
+        self.values
+    }
+
+    /// 按列名取值并转换为指定的类型
+    ///
+    /// 优先匹配完全相同的列名，其次忽略大小写匹配。
+    ///
+    /// # Arguments
+    /// * `column` - 列名
+    ///
+    /// # Returns
+    /// * `Result<T, ExecutionError>` - 转换结果，列不存在或值的类型不符时返回错误
+    pub fn get<T: FromValue>(&self, column: &str) -> Result<T, ExecutionError> {
+        let index = self
+            .columns
+            .iter()
+            .position(|name| name == column)
+            .or_else(|| {
+                self.columns
+                    .iter()
+                    .position(|name| name.eq_ignore_ascii_case(column))
+            })
+            .ok_or_else(|| {
+                ExecutionError::ExecutionError(format!("查询结果中不存在列 '{}'", column))
+            })?;
+        T::from_value(&self.values[index]).map_err(|err| {
+            ExecutionError::TypeUnmatch(format!("列 '{}' 的值无法转换: {}", column, err))
+        })
+    }
+
+    /// 按位置取值并转换为指定的类型
+    ///
+    /// # Arguments
+    /// * `index` - 列的位置，从 0 开始
+    ///
+    /// # Returns
+    /// * `Result<T, ExecutionError>` - 转换结果，位置越界或值的类型不符时返回错误
+    pub fn get_index<T: FromValue>(&self, index: usize) -> Result<T, ExecutionError> {
+        let value = self.values.get(index).ok_or_else(|| {
+            ExecutionError::ExecutionError(format!(
+                "查询结果只有 {} 列，不存在第 {} 列",
+                self.values.len(),
+                index + 1
+            ))
+        })?;
+        T::from_value(value)
+    }
+}
+
+/// 值的类型不符时的错误
+fn mismatch(value: &Value, type_name: &str) -> ExecutionError {
+    match value {
+        Value::Null => ExecutionError::TypeUnmatch(format!("NULL 无法转换为 {}", type_name)),
+        value => ExecutionError::TypeUnmatch(format!("'{}' 无法转换为 {}", value, type_name)),
+    }
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Result<Self, ExecutionError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: &Value) -> Result<Self, ExecutionError> {
+        match value {
+            Value::Int(i) => Ok(*i),
+            value => Err(mismatch(value, "i64")),
+        }
+    }
+}
+
+impl FromValue for f64 {
+    /// 整数也可以转换为浮点数
+    fn from_value(value: &Value) -> Result<Self, ExecutionError> {
+        match value {
+            Value::Float(f) => Ok(*f),
+            Value::Int(i) => Ok(*i as f64),
+            value => Err(mismatch(value, "f64")),
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, ExecutionError> {
+        match value {
+            Value::Varchar(s) => Ok(s.clone()),
+            value => Err(mismatch(value, "String")),
+        }
+    }
+}
+
+impl FromValue for bool {
+    /// 没有布尔类型的列，整数 `0` 和 `1` 也可以转换为布尔值
+    fn from_value(value: &Value) -> Result<Self, ExecutionError> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            Value::Int(0) => Ok(false),
+            Value::Int(1) => Ok(true),
+            value => Err(mismatch(value, "bool")),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    /// NULL 转换为 None，其他值按 `T` 转换
+    fn from_value(value: &Value) -> Result<Self, ExecutionError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+impl ToValue for Value {
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl ToValue for i64 {
+    fn to_value(&self) -> Value {
+        Value::Int(*self)
+    }
+}
+
+impl ToValue for i32 {
+    fn to_value(&self) -> Value {
+        Value::Int(i64::from(*self))
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(&self) -> Value {
+        Value::Float(*self)
+    }
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> Value {
+        Value::Varchar(self.to_string())
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::Varchar(self.clone())
+    }
+}
+
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl<T: ToValue + ?Sized> ToValue for &T {
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    /// None 转换为 NULL
+    fn to_value(&self) -> Value {
+        match self {
+            Some(value) => value.to_value(),
+            None => Value::Null,
+        }
+    }
+}
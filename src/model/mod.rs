@@ -2,9 +2,12 @@
 ///
 /// 包含数据库中使用的基本数据结构定义，如列、数据类型和值类型。
 /// 这些结构被序列化和反序列化以支持数据持久化。
+pub mod convert;
+
 use bincode::{Decode, Encode};
 use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 /// 表列定义结构
 ///
@@ -23,19 +26,129 @@ pub struct Column {
 
 /// 列数据类型枚举
 ///
-/// 支持整数和可变长度字符串两种基本类型，均可指定可选的长度限制。
-#[derive(Debug, Encode, Decode)]
+/// 支持各种宽度的有符号和无符号整数类型以及可变长度字符串类型，均可指定可选的长度限制。
+/// 整数值统一以 `i64` 存储，因此 `BIGINT UNSIGNED` 的上限与 `BIGINT` 相同。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
 pub enum ColumnDataType {
     /// 整数类型，可选的长度限制
     Int(Option<u64>),
     /// 字符串类型，可选的长度限制
     Varchar(Option<u64>),
+    /// 1 字节整数类型，可选的长度限制
+    TinyInt(Option<u64>),
+    /// 2 字节整数类型，可选的长度限制
+    SmallInt(Option<u64>),
+    /// 8 字节整数类型，可选的长度限制
+    BigInt(Option<u64>),
+    /// 无符号 1 字节整数类型，可选的长度限制
+    TinyIntUnsigned(Option<u64>),
+    /// 无符号 2 字节整数类型，可选的长度限制
+    SmallIntUnsigned(Option<u64>),
+    /// 无符号整数类型，可选的长度限制
+    IntUnsigned(Option<u64>),
+    /// 无符号 8 字节整数类型，可选的长度限制
+    BigIntUnsigned(Option<u64>),
+}
+
+impl ColumnDataType {
+    /// 返回类型的长度限制
+    pub fn length(&self) -> Option<u64> {
+        match *self {
+            ColumnDataType::Int(len)
+            | ColumnDataType::Varchar(len)
+            | ColumnDataType::TinyInt(len)
+            | ColumnDataType::SmallInt(len)
+            | ColumnDataType::BigInt(len)
+            | ColumnDataType::TinyIntUnsigned(len)
+            | ColumnDataType::SmallIntUnsigned(len)
+            | ColumnDataType::IntUnsigned(len)
+            | ColumnDataType::BigIntUnsigned(len) => len,
+        }
+    }
+
+    /// 返回整数类型可以存储的取值范围
+    ///
+    /// # Returns
+    /// * `Option<(i64, i64)>` - 最小值和最大值（均包含），非整数类型返回 None
+    pub fn integer_range(&self) -> Option<(i64, i64)> {
+        match self {
+            ColumnDataType::TinyInt(_) => Some((i8::MIN.into(), i8::MAX.into())),
+            ColumnDataType::SmallInt(_) => Some((i16::MIN.into(), i16::MAX.into())),
+            ColumnDataType::Int(_) => Some((i32::MIN.into(), i32::MAX.into())),
+            ColumnDataType::BigInt(_) => Some((i64::MIN, i64::MAX)),
+            ColumnDataType::TinyIntUnsigned(_) => Some((0, u8::MAX.into())),
+            ColumnDataType::SmallIntUnsigned(_) => Some((0, u16::MAX.into())),
+            ColumnDataType::IntUnsigned(_) => Some((0, u32::MAX.into())),
+            ColumnDataType::BigIntUnsigned(_) => Some((0, i64::MAX)),
+            ColumnDataType::Varchar(_) => None,
+        }
+    }
+
+    /// 判断是否为整数类型
+    pub fn is_integer(&self) -> bool {
+        self.integer_range().is_some()
+    }
+
+    /// 返回类型名称，不含长度和 `UNSIGNED` 修饰
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColumnDataType::Int(_) | ColumnDataType::IntUnsigned(_) => "INT",
+            ColumnDataType::Varchar(_) => "VARCHAR",
+            ColumnDataType::TinyInt(_) | ColumnDataType::TinyIntUnsigned(_) => "TINYINT",
+            ColumnDataType::SmallInt(_) | ColumnDataType::SmallIntUnsigned(_) => "SMALLINT",
+            ColumnDataType::BigInt(_) | ColumnDataType::BigIntUnsigned(_) => "BIGINT",
+        }
+    }
+
+    /// 判断是否为无符号整数类型
+    pub fn is_unsigned(&self) -> bool {
+        matches!(
+            self,
+            ColumnDataType::TinyIntUnsigned(_)
+                | ColumnDataType::SmallIntUnsigned(_)
+                | ColumnDataType::IntUnsigned(_)
+                | ColumnDataType::BigIntUnsigned(_)
+        )
+    }
+}
+
+impl fmt::Display for ColumnDataType {
+    /// 实现列数据类型的 SQL 表示
+    ///
+    /// 用于 `DESCRIBE`、`SHOW CREATE TABLE` 等语句生成类型描述。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())?;
+        if let Some(len) = self.length() {
+            write!(f, "({})", len)?;
+        }
+        if self.is_unsigned() {
+            write!(f, " UNSIGNED")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Column {
+    /// 实现列定义的 SQL 表示
+    ///
+    /// 生成可用于 `CREATE TABLE` 语句的列定义，包含列名、类型和约束。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)?;
+        if self.is_primary_key {
+            write!(f, " PRIMARY KEY")?;
+        } else if !self.is_nullable {
+            write!(f, " NOT NULL")?;
+        }
+        Ok(())
+    }
 }
 
 /// 值类型枚举
 ///
-/// 表示数据库中存储的实际值，支持整数、字符串、布尔值和 NULL。
-#[derive(Debug, Encode, Decode, Clone, PartialEq)]
+/// 表示数据库中存储的实际值，支持整数、字符串、布尔值、浮点数和 NULL。
+/// `Eq` 与 `Hash` 将两个 NULL 视为相等，用于 `DISTINCT` 等需要按值去重的场景；
+/// SQL 比较运算中 NULL 的语义由表达式求值器单独处理。
+#[derive(Debug, Encode, Decode, Clone)]
 pub enum Value {
     /// 整数值
     Int(i64),
@@ -45,6 +158,88 @@ pub enum Value {
     Bool(bool),
     /// NULL 值
     Null,
+    /// 浮点数值，由 `AVG` 等计算产生
+    Float(f64),
+}
+
+impl Value {
+    /// 将值转换为字符串，用于字符串拼接和模式匹配等需要文本的场景
+    ///
+    /// 数值使用其十进制形式，布尔值与 MySQL 一致转换为 `1`/`0`，NULL 返回 None。
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            Value::Null => None,
+            Value::Varchar(s) => Some(s.clone()),
+            Value::Bool(b) => Some(i64::from(*b).to_string()),
+            Value::Int(_) | Value::Float(_) => Some(self.to_string()),
+        }
+    }
+
+    /// 浮点数用于相等比较和哈希的位表示
+    ///
+    /// 将 `-0.0` 规范化为 `0.0`，保证二者相等且哈希值相同。
+    fn float_bits(f: f64) -> u64 {
+        if f == 0.0 {
+            0.0f64.to_bits()
+        } else {
+            f.to_bits()
+        }
+    }
+
+    /// 若浮点数恰好等于某个整数，返回该整数
+    ///
+    /// 用于整数与浮点数的相等比较和哈希，使 `1` 与 `1.0` 相等且哈希值相同。
+    fn float_as_int(f: f64) -> Option<i64> {
+        // i64 的范围为 [-2^63, 2^63)，边界均可用 f64 精确表示
+        if f.fract() == 0.0 && f >= i64::MIN as f64 && f < -(i64::MIN as f64) {
+            Some(f as i64)
+        } else {
+            None
+        }
+    }
+}
+
+impl PartialEq for Value {
+    /// 实现值的相等比较
+    ///
+    /// 浮点数按位比较，以满足 `Eq` 与 `Hash` 的一致性要求；整数与浮点数按数值比较，
+    /// 因此 `UNION`、`DISTINCT` 和 `GROUP BY` 会将 `1` 与 `1.0` 视为同一个值。
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Varchar(a), Value::Varchar(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Float(a), Value::Float(b)) => Value::float_bits(*a) == Value::float_bits(*b),
+            (Value::Int(a), Value::Float(b)) | (Value::Float(b), Value::Int(a)) => {
+                Value::float_as_int(*b) == Some(*a)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    /// 实现值的哈希，与 `PartialEq` 保持一致
+    ///
+    /// 等于某个整数的浮点数按该整数计算哈希。
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if let Value::Float(f) = self {
+            if let Some(i) = Value::float_as_int(*f) {
+                return Value::Int(i).hash(state);
+            }
+        }
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::Int(i) => i.hash(state),
+            Value::Varchar(s) => s.hash(state),
+            Value::Bool(b) => b.hash(state),
+            Value::Null => {}
+            Value::Float(f) => Value::float_bits(*f).hash(state),
+        }
+    }
 }
 
 impl fmt::Display for Value {
@@ -57,6 +252,7 @@ impl fmt::Display for Value {
             Value::Varchar(s) => write!(f, "{}", s),
             Value::Bool(b) => write!(f, "{}", b),
             Value::Null => write!(f, "NULL"),
+            Value::Float(x) => write!(f, "{}", x),
         }
     }
 }
@@ -71,6 +267,9 @@ impl PartialOrd for Value {
             (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
             (Value::Varchar(a), Value::Varchar(b)) => a.partial_cmp(b),
             (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+            (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+            (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
 
             // Null is considered less than everything else
             (Value::Null, Value::Null) => Some(Ordering::Equal),
@@ -96,10 +295,7 @@ impl Clone for Column {
     fn clone(&self) -> Self {
         Self {
             name: self.name.clone(),
-            data_type: match &self.data_type {
-                ColumnDataType::Int(len) => ColumnDataType::Int(len.clone()),
-                ColumnDataType::Varchar(len) => ColumnDataType::Varchar(len.clone()),
-            },
+            data_type: self.data_type,
             is_primary_key: self.is_primary_key,
             is_nullable: self.is_nullable,
         }
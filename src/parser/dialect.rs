@@ -0,0 +1,192 @@
+/// SQL 方言模块
+///
+/// 在 sqlparser 的 MySQL 方言基础上开启其未支持的 MySQL 语法（如 `GROUP BY ... WITH ROLLUP`、
+/// `MOD` 运算符、位移和按位取反运算符、`PREPARE ... FROM`），并支持 `$1` 形式的参数占位符。
+use std::any::TypeId;
+
+use sqlparser::ast::{BinaryOperator, Expr, Statement, UnaryOperator};
+use sqlparser::dialect::{Dialect, MySqlDialect, Precedence};
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::Token;
+
+use super::parse_without_unescape;
+use super::unescape::unescape_string;
+
+/// 数据库使用的 SQL 方言
+///
+/// 所有行为委托给 [`MySqlDialect`]，并且对解析器表现为 MySQL 方言，
+/// 以保留解析器内部针对 MySQL 的特殊处理（如 `LIMIT m, n`）。
+#[derive(Debug, Default)]
+pub struct SimpleDbDialect;
+
+/// 被委托的 MySQL 方言
+const MYSQL: MySqlDialect = MySqlDialect {};
+
+impl SimpleDbDialect {
+    /// 解析 `PREPARE name FROM 'sql'`，字符串中必须恰好包含一条语句
+    fn parse_prepare_from(&self, parser: &mut Parser) -> Result<Statement, ParserError> {
+        parser.expect_keyword_is(Keyword::PREPARE)?;
+        let name = parser.parse_identifier()?;
+        parser.expect_keyword_is(Keyword::FROM)?;
+        // 外层语句的转义处理不会进入字符串内部，这里先处理字符串本身的转义，
+        // 语句中的字符串字面量则随外层语句统一处理
+        let next_token = parser.next_token();
+        let sql = match next_token.token {
+            Token::SingleQuotedString(sql) => unescape_string(&sql, '\''),
+            Token::DoubleQuotedString(sql) => unescape_string(&sql, '"'),
+            _ => return parser.expected("字符串", next_token),
+        };
+        let mut statements = parse_without_unescape(self, &sql)?;
+        if statements.len() != 1 {
+            return Err(ParserError::ParserError(
+                "PREPARE 只能包含一条语句".to_string(),
+            ));
+        }
+        Ok(Statement::Prepare {
+            name,
+            data_types: vec![],
+            statement: Box::new(statements.remove(0)),
+        })
+    }
+}
+
+impl Dialect for SimpleDbDialect {
+    fn dialect(&self) -> TypeId {
+        TypeId::of::<MySqlDialect>()
+    }
+
+    /// `$` 开头的不是标识符，而是 `$1` 形式的参数占位符
+    fn is_identifier_start(&self, ch: char) -> bool {
+        ch != '$' && MYSQL.is_identifier_start(ch)
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        MYSQL.is_identifier_part(ch)
+    }
+
+    fn is_delimited_identifier_start(&self, ch: char) -> bool {
+        MYSQL.is_delimited_identifier_start(ch)
+    }
+
+    fn identifier_quote_style(&self, identifier: &str) -> Option<char> {
+        MYSQL.identifier_quote_style(identifier)
+    }
+
+    fn supports_string_literal_backslash_escape(&self) -> bool {
+        MYSQL.supports_string_literal_backslash_escape()
+    }
+
+    fn supports_numeric_prefix(&self) -> bool {
+        MYSQL.supports_numeric_prefix()
+    }
+
+    /// 解析按位取反运算符 `~`
+    fn parse_prefix(&self, parser: &mut Parser) -> Option<Result<Expr, ParserError>> {
+        if !parser.consume_token(&Token::Tilde) {
+            return MYSQL.parse_prefix(parser);
+        }
+        Some(
+            parser
+                .parse_subexpr(self.prec_value(Precedence::MulDivModOp))
+                .map(|expr| Expr::UnaryOp {
+                    op: UnaryOperator::PGBitwiseNot,
+                    expr: Box::new(expr),
+                }),
+        )
+    }
+
+    /// 解析 `DIV`、`MOD`、`<<` 和 `>>` 运算符
+    ///
+    /// [`MySqlDialect`] 以最低优先级解析 `DIV` 的右操作数（`10 DIV 3 + 1` 被解析为 `10 DIV (3 + 1)`），
+    /// 并且在右操作数有误时直接 panic，这里改为按运算符自身的优先级解析。
+    fn parse_infix(
+        &self,
+        parser: &mut Parser,
+        expr: &Expr,
+        precedence: u8,
+    ) -> Option<Result<Expr, ParserError>> {
+        let op = if parser.parse_keyword(Keyword::DIV) {
+            BinaryOperator::MyIntegerDivide
+        } else if parser.parse_keyword(Keyword::MOD) {
+            BinaryOperator::Modulo
+        } else if parser.consume_token(&Token::ShiftLeft) {
+            BinaryOperator::PGBitwiseShiftLeft
+        } else if parser.consume_token(&Token::ShiftRight) {
+            BinaryOperator::PGBitwiseShiftRight
+        } else {
+            return MYSQL.parse_infix(parser, expr, precedence);
+        };
+        Some(
+            parser
+                .parse_subexpr(precedence)
+                .map(|right| Expr::BinaryOp {
+                    left: Box::new(expr.clone()),
+                    op,
+                    right: Box::new(right),
+                }),
+        )
+    }
+
+    /// `MOD` 运算符与 `*`、`/`、`%` 优先级相同
+    fn get_next_precedence(&self, parser: &Parser) -> Option<Result<u8, ParserError>> {
+        match parser.peek_token().token {
+            Token::Word(word) if word.keyword == Keyword::MOD => {
+                Some(Ok(self.prec_value(Precedence::MulDivModOp)))
+            }
+            _ => MYSQL.get_next_precedence(parser),
+        }
+    }
+
+    /// 解析 MySQL 的 `PREPARE name FROM 'sql'`
+    ///
+    /// 字符串中的语句在这里解析，结果与 `PREPARE name AS sql` 一样表示为 [`Statement::Prepare`]。
+    fn parse_statement(&self, parser: &mut Parser) -> Option<Result<Statement, ParserError>> {
+        let is_keyword = |n: usize, keyword: Keyword| matches!(&parser.peek_nth_token(n).token, Token::Word(word) if word.keyword == keyword);
+        if !(is_keyword(0, Keyword::PREPARE) && is_keyword(2, Keyword::FROM)) {
+            return MYSQL.parse_statement(parser);
+        }
+        Some(self.parse_prepare_from(parser))
+    }
+
+    fn require_interval_qualifier(&self) -> bool {
+        MYSQL.require_interval_qualifier()
+    }
+
+    fn supports_limit_comma(&self) -> bool {
+        MYSQL.supports_limit_comma()
+    }
+
+    fn supports_create_table_select(&self) -> bool {
+        MYSQL.supports_create_table_select()
+    }
+
+    fn supports_insert_set(&self) -> bool {
+        MYSQL.supports_insert_set()
+    }
+
+    fn supports_user_host_grantee(&self) -> bool {
+        MYSQL.supports_user_host_grantee()
+    }
+
+    fn is_table_factor_alias(&self, explicit: bool, kw: &Keyword, parser: &mut Parser) -> bool {
+        MYSQL.is_table_factor_alias(explicit, kw, parser)
+    }
+
+    fn supports_table_hints(&self) -> bool {
+        MYSQL.supports_table_hints()
+    }
+
+    fn requires_single_line_comment_whitespace(&self) -> bool {
+        MYSQL.requires_single_line_comment_whitespace()
+    }
+
+    fn supports_match_against(&self) -> bool {
+        MYSQL.supports_match_against()
+    }
+
+    /// MySQL 支持 `GROUP BY ... WITH ROLLUP`
+    fn supports_group_by_with_modifier(&self) -> bool {
+        true
+    }
+}
@@ -2,16 +2,20 @@
 ///
 /// 用于解析 SQL 语句并转换为内部表示形式，供执行器执行。
 /// 使用外部 sqlparser 库完成基本的 SQL 语法解析工作。
-use sqlparser::dialect::MySqlDialect;
-use sqlparser::parser::Parser;
+use sqlparser::ast::{Statement, VisitMut};
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::{Parser, ParserOptions};
 
+pub mod dialect;
 pub mod error;
+pub mod unescape;
 
+pub use dialect::SimpleDbDialect;
 pub use error::{ParserError, ParserResult};
 
 /// 解析 SQL 语句
 ///
-/// 将 SQL 字符串解析为语句向量，供后续执行。使用通用 SQL 方言。
+/// 将 SQL 字符串解析为语句向量，供后续执行。使用基于 MySQL 的 [`SimpleDbDialect`] 方言。
 ///
 /// # Arguments
 ///
@@ -20,10 +24,39 @@ pub use error::{ParserError, ParserResult};
 /// # Returns
 ///
 /// 成功时返回语句向量，失败时返回解析错误
-pub fn parse_sql(sql: &str) -> ParserResult<Vec<sqlparser::ast::Statement>> {
-    let dialect = MySqlDialect {};
-    match Parser::parse_sql(&dialect, sql) {
-        Ok(ast) => Ok(ast),
+pub fn parse_sql(sql: &str) -> ParserResult<Vec<Statement>> {
+    let dialect = SimpleDbDialect;
+    match parse_without_unescape(&dialect, sql) {
+        Ok(mut ast) => {
+            let _ = ast.visit(&mut unescape::StringUnescaper);
+            Ok(ast)
+        }
         Err(e) => Err(ParserError::SqlParseError(e.to_string())),
     }
 }
+
+/// 解析 SQL 语句，字符串字面量保留原样，不处理其中的转义序列
+///
+/// 转义序列由 [`unescape::StringUnescaper`] 在整条语句解析完成后统一处理，
+/// 嵌套解析的语句（如 `PREPARE ... FROM` 中的语句）因此只处理一次转义。
+///
+/// # Arguments
+///
+/// * `dialect` - SQL 方言
+/// * `sql` - 要解析的 SQL 字符串
+///
+/// # Returns
+///
+/// 成功时返回语句向量，失败时返回 sqlparser 的解析错误
+fn parse_without_unescape(
+    dialect: &dyn Dialect,
+    sql: &str,
+) -> Result<Vec<Statement>, sqlparser::parser::ParserError> {
+    let options = ParserOptions::new()
+        .with_trailing_commas(dialect.supports_trailing_commas())
+        .with_unescape(false);
+    Parser::new(dialect)
+        .with_options(options)
+        .try_with_sql(sql)?
+        .parse_statements()
+}
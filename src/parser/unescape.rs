@@ -0,0 +1,66 @@
+/// 字符串字面量转义模块
+///
+/// sqlparser 的 MySQL 分词器会去掉所有转义序列中的反斜杠，使 `'a\_c'` 变成 `a_c`，
+/// 在 `LIKE` 中匹配任意字符。MySQL 则保留 `\%` 和 `\_` 中的反斜杠，以便在 `LIKE` 中按字面匹配。
+/// 因此解析时关闭分词器的转义处理，由本模块按 MySQL 的规则处理字符串字面量中的转义序列。
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{Value, VisitorMut};
+
+/// 按 MySQL 的规则处理字符串中的转义序列
+///
+/// 反斜杠转义序列中，`\0`、`\b`、`\n`、`\r`、`\t`、`\Z` 转换为对应的控制字符，
+/// `\%` 和 `\_` 保持不变，其余的去掉反斜杠；连续两个引号转换为一个引号。
+///
+/// # Arguments
+///
+/// * `s` - 分词器保留原样的字符串内容，不含两端的引号
+/// * `quote` - 字符串两端的引号
+///
+/// # Returns
+///
+/// * `String` - 处理转义序列后的字符串
+pub fn unescape_string(s: &str, quote: char) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('0') => result.push('\0'),
+                Some('b') => result.push('\u{8}'),
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some('t') => result.push('\t'),
+                Some('Z') => result.push('\u{1a}'),
+                Some(c @ ('%' | '_')) => {
+                    result.push('\\');
+                    result.push(c);
+                }
+                Some(c) => result.push(c),
+                None => result.push('\\'),
+            },
+            c if c == quote && chars.peek() == Some(&quote) => {
+                chars.next();
+                result.push(quote);
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// 处理语法树中所有字符串字面量的转义序列的访问器
+pub struct StringUnescaper;
+
+impl VisitorMut for StringUnescaper {
+    type Break = ();
+
+    fn post_visit_value(&mut self, value: &mut Value) -> ControlFlow<Self::Break> {
+        match value {
+            Value::SingleQuotedString(s) => *s = unescape_string(s, '\''),
+            Value::DoubleQuotedString(s) => *s = unescape_string(s, '"'),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
@@ -45,6 +45,36 @@ lazy_static! {
             "DEFAULT",
             "INDEX",
             "VIEW",
+            "TRUNCATE",
+            "RENAME",
+            "TO",
+            "SHOW",
+            "TABLES",
+            "COLUMNS",
+            "DESCRIBE",
+            "ROLLUP",
+            "INNER",
+            "LEFT",
+            "RIGHT",
+            "FULL",
+            "OUTER",
+            "CROSS",
+            "USING",
+            "NATURAL",
+            "RECURSIVE",
+            "OVER",
+            "PARTITION",
+            "ROWS",
+            "RANGE",
+            "UNBOUNDED",
+            "PRECEDING",
+            "FOLLOWING",
+            "CURRENT",
+            "SIGNED",
+            "UNSIGNED",
+            "PREPARE",
+            "EXECUTE",
+            "DEALLOCATE",
         ];
         Regex::new(&format!(
             "(?i){}",
@@ -63,14 +93,62 @@ lazy_static! {
             "AND",
             "OR",
             "NOT",
+            "XOR",
+            "DIV",
+            "MOD",
             "COUNT",
             "SUM",
             "AVG",
             "MAX",
             "MIN",
+            "CONCAT",
+            "CONCAT_WS",
+            "UPPER",
+            "UCASE",
+            "LOWER",
+            "LCASE",
+            "LENGTH",
+            "CHAR_LENGTH",
+            "SUBSTRING",
+            "SUBSTR",
+            "TRIM",
+            "LTRIM",
+            "RTRIM",
+            "LEADING",
+            "TRAILING",
+            "BOTH",
+            "REPLACE",
+            "LPAD",
+            "RPAD",
+            "INSTR",
+            "ABS",
+            "ROUND",
+            "FLOOR",
+            "CEIL",
+            "CEILING",
+            "POWER",
+            "POW",
+            "SQRT",
+            "GREATEST",
+            "LEAST",
+            "COALESCE",
+            "IFNULL",
+            "NULLIF",
+            "IF",
+            "CAST",
+            "CONVERT",
             "LIKE",
+            "ILIKE",
+            "REGEXP",
+            "RLIKE",
+            "ESCAPE",
             "IN",
             "BETWEEN",
+            "CASE",
+            "WHEN",
+            "THEN",
+            "ELSE",
+            "END",
             "IS",
             "EXISTS",
             "AS",
@@ -78,6 +156,8 @@ lazy_static! {
             "WITH",
             "UNION",
             "INTERSECT",
+            "EXCEPT",
+            "ALL",
         ];
         Regex::new(&format!(
             "(?i){}",
@@ -0,0 +1,347 @@
+/// 聚合函数模块
+///
+/// 提供 `COUNT`、`SUM`、`AVG`、`MIN`、`MAX` 等聚合函数的累加器实现，
+/// 以及判断表达式中是否包含聚合函数的工具函数。
+/// 嵌入数据库的程序可以实现 `AggregateFunction` 并通过 `register_aggregate_function` 注册自定义聚合函数。
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+use sqlparser::ast::{Expr, Function, Query, Visit, Visitor};
+
+use crate::executor::ExecutionError;
+use crate::model::Value;
+use crate::utils::function;
+
+/// 聚合函数累加器
+///
+/// 聚合时对分组中的每一行调用一次 `step`，最后调用 `finish` 得到聚合结果。
+pub trait Accumulator {
+    /// 累加一个参数值
+    ///
+    /// # Arguments
+    /// * `value` - 当前行的参数值，可能为 NULL
+    fn step(&mut self, value: &Value) -> Result<(), ExecutionError>;
+
+    /// 返回聚合结果
+    fn finish(&self) -> Result<Value, ExecutionError>;
+}
+
+/// 用户定义的聚合函数
+///
+/// 每个分组聚合时先调用 `init` 创建初始状态，对分组中的每一行调用 `step` 更新状态，
+/// 最后调用 `finish` 由状态得到聚合结果。NULL 参数值也会传给 `step`，
+/// 使用 `DISTINCT` 时只传入首次出现的非 NULL 值。
+pub trait AggregateFunction: Send + Sync {
+    /// 一个分组的聚合状态
+    type State;
+
+    /// 创建一个分组的初始状态
+    fn init(&self) -> Self::State;
+
+    /// 累加一个参数值
+    ///
+    /// # Arguments
+    /// * `state` - 当前分组的聚合状态
+    /// * `value` - 当前行的参数值，可能为 NULL
+    fn step(&self, state: &mut Self::State, value: &Value) -> Result<(), ExecutionError>;
+
+    /// 由聚合状态得到聚合结果
+    ///
+    /// # Arguments
+    /// * `state` - 累加完分组中所有行之后的聚合状态
+    fn finish(&self, state: &Self::State) -> Result<Value, ExecutionError>;
+}
+
+/// 创建用户定义的聚合函数的累加器
+type AccumulatorFactory = Arc<dyn Fn() -> Box<dyn Accumulator> + Send + Sync>;
+
+lazy_static! {
+    /// 用户定义的聚合函数，以大写的函数名为键
+    static ref USER_AGGREGATES: RwLock<HashMap<String, AccumulatorFactory>> =
+        RwLock::new(HashMap::new());
+}
+
+/// 注册用户定义的聚合函数
+///
+/// 注册后即可在 SQL 中像内置聚合函数一样调用，包括 `GROUP BY`、`DISTINCT` 和 `OVER` 子句，
+/// 函数名大小写不敏感。再次注册同名的函数时替换之前的实现。
+///
+/// # Arguments
+/// * `name` - 函数名，只能包含字母、数字和下划线
+/// * `aggregate` - 聚合函数的实现
+///
+/// # Returns
+/// * `Result<(), ExecutionError>` - 函数名无效，或与内置聚合函数、标量函数重名时返回错误
+pub fn register_aggregate_function<A>(name: &str, aggregate: A) -> Result<(), ExecutionError>
+where
+    A: AggregateFunction + 'static,
+{
+    let name = function::validate_function_name(name)?;
+    if is_builtin_aggregate(&name) || function::is_scalar_function(&name) {
+        return Err(ExecutionError::ExecutionError(format!(
+            "函数 {} 已存在",
+            name
+        )));
+    }
+    let aggregate = Arc::new(aggregate);
+    let factory: AccumulatorFactory = Arc::new(move || {
+        Box::new(UserAccumulator {
+            state: aggregate.init(),
+            aggregate: aggregate.clone(),
+        })
+    });
+    USER_AGGREGATES.write().unwrap().insert(name, factory);
+    Ok(())
+}
+
+/// 判断函数名是否为聚合函数，包括内置聚合函数和用户定义的聚合函数
+///
+/// # Arguments
+/// * `name` - 函数名，大小写不敏感
+pub fn is_aggregate_function(name: &str) -> bool {
+    let name = name.to_uppercase();
+    is_builtin_aggregate(&name) || USER_AGGREGATES.read().unwrap().contains_key(&name)
+}
+
+/// 判断大写的函数名是否为内置聚合函数
+fn is_builtin_aggregate(name: &str) -> bool {
+    matches!(name, "COUNT" | "SUM" | "AVG" | "MIN" | "MAX")
+}
+
+/// 创建聚合函数的累加器
+///
+/// # Arguments
+/// * `name` - 函数名，大小写不敏感
+/// * `is_distinct` - 是否只累加不重复的参数值，对应 `COUNT(DISTINCT x)` 等写法
+///
+/// # Returns
+/// * `Option<Box<dyn Accumulator>>` - 函数名不是聚合函数时返回 None
+pub fn create_accumulator(name: &str, is_distinct: bool) -> Option<Box<dyn Accumulator>> {
+    let name = name.to_uppercase();
+    let accumulator: Box<dyn Accumulator> = match name.as_str() {
+        "COUNT" => Box::new(Count::default()),
+        "SUM" => Box::new(Sum::default()),
+        "AVG" => Box::new(Avg::default()),
+        "MIN" => Box::new(Extremum::new(std::cmp::Ordering::Less)),
+        "MAX" => Box::new(Extremum::new(std::cmp::Ordering::Greater)),
+        _ => {
+            let factory = USER_AGGREGATES.read().unwrap().get(&name)?.clone();
+            factory()
+        }
+    };
+    if is_distinct {
+        Some(Box::new(Distinct {
+            seen: HashSet::new(),
+            inner: accumulator,
+        }))
+    } else {
+        Some(accumulator)
+    }
+}
+
+/// 判断表达式中是否包含聚合函数
+///
+/// 子查询中的聚合函数属于子查询本身，不计入外层表达式；
+/// 带 `OVER` 子句的函数是窗口函数，也不视为聚合函数。
+///
+/// # Arguments
+/// * `expr` - 要检查的表达式
+pub fn contains_aggregate(expr: &Expr) -> bool {
+    let mut finder = AggregateFinder { query_depth: 0 };
+    expr.visit(&mut finder).is_break()
+}
+
+/// 判断函数调用是否为聚合函数调用
+///
+/// # Arguments
+/// * `function` - 函数调用表达式
+pub fn is_aggregate_call(function: &Function) -> bool {
+    function.over.is_none() && is_aggregate_function(&function.name.to_string())
+}
+
+/// 查找聚合函数的访问器
+struct AggregateFinder {
+    /// 当前所在子查询的嵌套深度
+    query_depth: usize,
+}
+
+impl Visitor for AggregateFinder {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        self.query_depth += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        self.query_depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        match expr {
+            Expr::Function(function) if self.query_depth == 0 && is_aggregate_call(function) => {
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// `COUNT`：统计非 NULL 值的个数，`COUNT(*)` 的每一行以非 NULL 值传入
+#[derive(Default)]
+struct Count {
+    count: i64,
+}
+
+impl Accumulator for Count {
+    fn step(&mut self, value: &Value) -> Result<(), ExecutionError> {
+        if !matches!(value, Value::Null) {
+            self.count += 1;
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<Value, ExecutionError> {
+        Ok(Value::Int(self.count))
+    }
+}
+
+/// `SUM`：忽略 NULL，没有非 NULL 值时结果为 NULL
+#[derive(Default)]
+struct Sum {
+    sum: Option<Value>,
+}
+
+impl Accumulator for Sum {
+    fn step(&mut self, value: &Value) -> Result<(), ExecutionError> {
+        let sum = match (self.sum.take(), value) {
+            (sum, Value::Null) => sum,
+            (None, Value::Int(_) | Value::Float(_)) => Some(value.clone()),
+            (Some(Value::Int(a)), Value::Int(b)) => Some(Value::Int(a.checked_add(*b).ok_or(
+                ExecutionError::ExecutionError("SUM 结果超出整数范围".to_string()),
+            )?)),
+            (Some(Value::Int(a)), Value::Float(b)) => Some(Value::Float(a as f64 + b)),
+            (Some(Value::Float(a)), Value::Int(b)) => Some(Value::Float(a + *b as f64)),
+            (Some(Value::Float(a)), Value::Float(b)) => Some(Value::Float(a + b)),
+            _ => {
+                return Err(ExecutionError::TypeUnmatch(format!(
+                    "SUM 的参数必须是数值，实际为 {}",
+                    value
+                )))
+            }
+        };
+        self.sum = sum;
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<Value, ExecutionError> {
+        Ok(self.sum.clone().unwrap_or(Value::Null))
+    }
+}
+
+/// `AVG`：忽略 NULL，结果为浮点数，没有非 NULL 值时结果为 NULL
+#[derive(Default)]
+struct Avg {
+    sum: f64,
+    count: i64,
+}
+
+impl Accumulator for Avg {
+    fn step(&mut self, value: &Value) -> Result<(), ExecutionError> {
+        match value {
+            Value::Null => return Ok(()),
+            Value::Int(i) => self.sum += *i as f64,
+            Value::Float(f) => self.sum += f,
+            _ => {
+                return Err(ExecutionError::TypeUnmatch(format!(
+                    "AVG 的参数必须是数值，实际为 {}",
+                    value
+                )))
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<Value, ExecutionError> {
+        if self.count == 0 {
+            Ok(Value::Null)
+        } else {
+            Ok(Value::Float(self.sum / self.count as f64))
+        }
+    }
+}
+
+/// `MIN` / `MAX`：忽略 NULL，没有非 NULL 值时结果为 NULL
+struct Extremum {
+    /// 新值与当前值比较结果为该方向时替换当前值
+    direction: std::cmp::Ordering,
+    current: Option<Value>,
+}
+
+impl Extremum {
+    fn new(direction: std::cmp::Ordering) -> Self {
+        Self {
+            direction,
+            current: None,
+        }
+    }
+}
+
+impl Accumulator for Extremum {
+    fn step(&mut self, value: &Value) -> Result<(), ExecutionError> {
+        if matches!(value, Value::Null) {
+            return Ok(());
+        }
+        let replace = match &self.current {
+            None => true,
+            Some(current) => value.partial_cmp(current) == Some(self.direction),
+        };
+        if replace {
+            self.current = Some(value.clone());
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<Value, ExecutionError> {
+        Ok(self.current.clone().unwrap_or(Value::Null))
+    }
+}
+
+/// `DISTINCT` 修饰：只把首次出现的非 NULL 值传给内部累加器
+struct Distinct {
+    seen: HashSet<Value>,
+    inner: Box<dyn Accumulator>,
+}
+
+impl Accumulator for Distinct {
+    fn step(&mut self, value: &Value) -> Result<(), ExecutionError> {
+        if matches!(value, Value::Null) || !self.seen.insert(value.clone()) {
+            return Ok(());
+        }
+        self.inner.step(value)
+    }
+
+    fn finish(&self) -> Result<Value, ExecutionError> {
+        self.inner.finish()
+    }
+}
+
+/// 用户定义的聚合函数的累加器，保存一个分组的聚合状态
+struct UserAccumulator<A: AggregateFunction> {
+    aggregate: Arc<A>,
+    state: A::State,
+}
+
+impl<A: AggregateFunction> Accumulator for UserAccumulator<A> {
+    fn step(&mut self, value: &Value) -> Result<(), ExecutionError> {
+        self.aggregate.step(&mut self.state, value)
+    }
+
+    fn finish(&self) -> Result<Value, ExecutionError> {
+        self.aggregate.finish(&self.state)
+    }
+}
@@ -0,0 +1,294 @@
+/// 类型转换模块
+///
+/// 实现 `CAST`/`CONVERT` 的显式类型转换，以及比较、算术运算和写入列时的隐式类型转换。
+/// 字符串按 MySQL 的规则转换为数值：忽略开头的空白，取最长的数值前缀，没有数值前缀时为 0。
+/// 与 MySQL 一致，严格模式只约束写入列时的隐式转换：字符串除数值和首尾空白外还有其他内容时报错。
+/// 比较、算术运算和显式的 `CAST` 不受严格模式影响，总是按宽松规则转换；
+/// 但 `CAST` 为整数时，超出 BIGINT 范围的数值总是报错。
+use std::sync::atomic::Ordering;
+
+use sqlparser::ast::{CharacterLength, DataType, ExactNumberInfo};
+
+use crate::executor::ExecutionError;
+use crate::model::{ColumnDataType, Value};
+use crate::utils::IS_STRICT_MODE;
+
+/// `CAST` 和 `CONVERT` 的目标类型
+#[derive(Debug, Clone, Copy)]
+pub enum CastTarget {
+    /// 有符号整数，对应 `SIGNED` 和各种整数类型
+    Signed,
+    /// 无符号整数，对应 `UNSIGNED`
+    Unsigned,
+    /// 字符串，可选的最大字符数
+    Char(Option<u64>),
+    /// 浮点数，对应 `DOUBLE`、`FLOAT` 和 `REAL`
+    Double,
+    /// 定点数，以保留指定小数位数的浮点数表示
+    Decimal(u64),
+}
+
+impl CastTarget {
+    /// 从 SQL 数据类型解析转换目标
+    ///
+    /// # Arguments
+    /// * `data_type` - `CAST(x AS type)` 中的类型
+    ///
+    /// # Returns
+    /// * `Result<CastTarget, ExecutionError>` - 转换目标，类型不受支持时返回错误
+    pub fn from_data_type(data_type: &DataType) -> Result<Self, ExecutionError> {
+        match data_type {
+            DataType::Signed
+            | DataType::SignedInteger
+            | DataType::Int(_)
+            | DataType::Integer(_)
+            | DataType::TinyInt(_)
+            | DataType::SmallInt(_)
+            | DataType::BigInt(_) => Ok(CastTarget::Signed),
+            DataType::Unsigned | DataType::UnsignedInteger => Ok(CastTarget::Unsigned),
+            DataType::Char(length)
+            | DataType::Character(length)
+            | DataType::Varchar(length)
+            | DataType::Nvarchar(length) => Ok(CastTarget::Char(match length {
+                Some(CharacterLength::IntegerLength { length, .. }) => Some(*length),
+                _ => None,
+            })),
+            DataType::Text => Ok(CastTarget::Char(None)),
+            DataType::Double(_)
+            | DataType::DoublePrecision
+            | DataType::Float(_)
+            | DataType::Real => Ok(CastTarget::Double),
+            DataType::Decimal(info) | DataType::Numeric(info) | DataType::Dec(info) => {
+                Ok(CastTarget::Decimal(match info {
+                    ExactNumberInfo::PrecisionAndScale(_, scale) => *scale,
+                    _ => 0,
+                }))
+            }
+            _ => Err(ExecutionError::ExecutionError(format!(
+                "不支持转换为类型 {}",
+                data_type
+            ))),
+        }
+    }
+}
+
+/// 执行显式类型转换
+///
+/// # Arguments
+/// * `value` - 要转换的值
+/// * `target` - 目标类型
+///
+/// # Returns
+/// * `Ok(Value)` - 转换后的值，NULL 转换后仍为 NULL
+/// * `Err(ExecutionError)` - 负数转换为 `UNSIGNED`，或转换为整数时超出 BIGINT 的范围
+pub fn cast(value: Value, target: CastTarget) -> Result<Value, ExecutionError> {
+    if matches!(value, Value::Null) {
+        return Ok(Value::Null);
+    }
+    match target {
+        CastTarget::Signed => Ok(Value::Int(cast_integer(&value)?)),
+        CastTarget::Unsigned => match cast_integer(&value)? {
+            i if i < 0 => Err(ExecutionError::ExecutionError(format!(
+                "值 {} 超出 UNSIGNED 的范围",
+                i
+            ))),
+            i => Ok(Value::Int(i)),
+        },
+        CastTarget::Char(length) => {
+            let text = value.to_text().unwrap_or_default();
+            Ok(Value::Varchar(match length {
+                Some(length) => text.chars().take(length as usize).collect(),
+                None => text,
+            }))
+        }
+        CastTarget::Double => Ok(Value::Float(to_float(&value, false)?)),
+        CastTarget::Decimal(scale) => {
+            let factor = 10f64.powi(scale.min(30) as i32);
+            Ok(Value::Float(
+                (to_float(&value, false)? * factor).round() / factor,
+            ))
+        }
+    }
+}
+
+/// 将写入列的值隐式转换为列的类型
+///
+/// 整数列接受数值字符串，浮点数四舍五入为整数；字符串列接受数值，转换为其十进制形式。
+/// 转换后的值仍需通过表的约束检查。
+///
+/// # Arguments
+/// * `value` - 要写入的值
+/// * `data_type` - 列的类型
+///
+/// # Returns
+/// * `Result<Value, ExecutionError>` - 转换后的值；严格模式下字符串不是有效的数值，
+///   或数值超出 BIGINT 的范围时返回错误
+pub fn coerce_for_column(
+    value: Value,
+    data_type: &ColumnDataType,
+) -> Result<Value, ExecutionError> {
+    match value {
+        Value::Varchar(_) | Value::Float(_) | Value::Bool(_) if data_type.is_integer() => {
+            Ok(Value::Int(to_integer(&value, is_strict_mode())?))
+        }
+        Value::Int(_) | Value::Float(_) | Value::Bool(_) if !data_type.is_integer() => {
+            Ok(Value::Varchar(value.to_text().unwrap_or_default()))
+        }
+        value => Ok(value),
+    }
+}
+
+/// 隐式转换二元运算的操作数
+///
+/// 参与算术运算的字符串和布尔值都转换为数值；比较运算中两侧类型不同时，
+/// 字符串和布尔值转换为数值后再比较（如 `TRUE = 1`），两个字符串或两个布尔值之间直接比较。
+/// 布尔值视为 `1`/`0`。
+///
+/// # Arguments
+/// * `left` - 左操作数
+/// * `right` - 右操作数
+/// * `is_arithmetic` - 是否为算术运算
+///
+/// # Returns
+/// * `Result<(Value, Value), ExecutionError>` - 转换后的操作数
+pub fn coerce_operands(
+    left: Value,
+    right: Value,
+    is_arithmetic: bool,
+) -> Result<(Value, Value), ExecutionError> {
+    if matches!(left, Value::Null) || matches!(right, Value::Null) {
+        return Ok((left, right));
+    }
+    if is_arithmetic || std::mem::discriminant(&left) != std::mem::discriminant(&right) {
+        Ok((implicit_number(&left)?, implicit_number(&right)?))
+    } else {
+        Ok((left, right))
+    }
+}
+
+/// 判断当前是否启用严格模式
+pub fn is_strict_mode() -> bool {
+    IS_STRICT_MODE.load(Ordering::Relaxed)
+}
+
+/// 按宽松规则将字符串或布尔值转换为数值
+fn implicit_number(value: &Value) -> Result<Value, ExecutionError> {
+    match value {
+        Value::Varchar(s) => parse_number(s, false),
+        Value::Bool(b) => Ok(Value::Int(i64::from(*b))),
+        other => Ok(other.clone()),
+    }
+}
+
+/// 显式转换为整数：字符串按宽松规则取数值前缀，但数值超出 BIGINT 的范围时总是报错
+fn cast_integer(value: &Value) -> Result<i64, ExecutionError> {
+    match implicit_number(value)? {
+        Value::Float(f) => round_to_integer(f, true),
+        number => to_integer(&number, false),
+    }
+}
+
+/// 将值转换为整数，浮点数四舍五入
+///
+/// 严格模式下字符串必须是有效的数值，且数值不能超出 BIGINT 的范围；
+/// 否则超出范围的数值截断为 BIGINT 的最大值或最小值。
+fn to_integer(value: &Value, strict: bool) -> Result<i64, ExecutionError> {
+    match value {
+        Value::Int(i) => Ok(*i),
+        Value::Bool(b) => Ok(i64::from(*b)),
+        Value::Float(f) => round_to_integer(*f, strict),
+        Value::Varchar(s) => to_integer(&parse_number(s, strict)?, strict),
+        Value::Null => Ok(0),
+    }
+}
+
+/// 将浮点数四舍五入为整数
+///
+/// # Arguments
+/// * `f` - 要转换的浮点数
+/// * `check_range` - 是否在结果不是有限值或超出 BIGINT 的范围时报错，为 false 时截断到 BIGINT 的范围
+fn round_to_integer(f: f64, check_range: bool) -> Result<i64, ExecutionError> {
+    let rounded = f.round();
+    // i64 的范围为 [-2^63, 2^63)，边界均可用 f64 精确表示
+    if rounded >= i64::MIN as f64 && rounded < -(i64::MIN as f64) {
+        Ok(rounded as i64)
+    } else if check_range {
+        Err(ExecutionError::ExecutionError(format!(
+            "值 {} 超出 BIGINT 的范围",
+            f
+        )))
+    } else {
+        Ok(rounded as i64)
+    }
+}
+
+/// 将值转换为浮点数
+fn to_float(value: &Value, strict: bool) -> Result<f64, ExecutionError> {
+    match value {
+        Value::Int(i) => Ok(*i as f64),
+        Value::Bool(b) => Ok(f64::from(u8::from(*b))),
+        Value::Float(f) => Ok(*f),
+        Value::Varchar(s) => to_float(&parse_number(s, strict)?, strict),
+        Value::Null => Ok(0.0),
+    }
+}
+
+/// 将字符串解析为数值
+///
+/// 取开头最长的数值前缀：不含小数点和指数且在整数范围内时为整数，否则为浮点数。
+///
+/// # Arguments
+/// * `s` - 要解析的字符串
+/// * `strict` - 是否要求整个字符串（除首尾空白外）都是数值
+///
+/// # Returns
+/// * `Result<Value, ExecutionError>` - 解析出的数值；严格模式下字符串不是有效的数值时返回错误
+fn parse_number(s: &str, strict: bool) -> Result<Value, ExecutionError> {
+    let trimmed = s.trim_start();
+    let bytes = trimmed.as_bytes();
+    let digits_from = |start: usize| {
+        (start..bytes.len())
+            .find(|&i| !bytes[i].is_ascii_digit())
+            .unwrap_or(bytes.len())
+    };
+
+    let mut end = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let integer_end = digits_from(end);
+    let mut has_digits = integer_end > end;
+    let mut is_float = false;
+    end = integer_end;
+    if bytes.get(end) == Some(&b'.') {
+        let fraction_end = digits_from(end + 1);
+        if has_digits || fraction_end > end + 1 {
+            has_digits = true;
+            is_float = true;
+            end = fraction_end;
+        }
+    }
+    if has_digits && matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exponent_end = digits_from(end + 1 + sign);
+        if exponent_end > end + 1 + sign {
+            is_float = true;
+            end = exponent_end;
+        }
+    }
+
+    let is_exact = has_digits && trimmed[end..].trim().is_empty();
+    if strict && !is_exact {
+        return Err(ExecutionError::TypeUnmatch(format!(
+            "无法将字符串 '{}' 转换为数值",
+            s
+        )));
+    }
+    if !has_digits {
+        return Ok(Value::Int(0));
+    }
+    let number = &trimmed[..end];
+    if !is_float {
+        if let Ok(i) = number.parse::<i64>() {
+            return Ok(Value::Int(i));
+        }
+    }
+    Ok(Value::Float(number.parse::<f64>().unwrap_or(0.0)))
+}
@@ -1,10 +1,156 @@
 /// 表达式求值模块
 ///
 /// 提供 SQL 表达式求值能力，支持比较操作、算术运算和逻辑运算等。
-use sqlparser::ast::{BinaryOperator as BinOp, Expr, Value as SqlValue};
+use sqlparser::ast::{
+    BinaryOperator as BinOp, CeilFloorKind, DateTimeField, DuplicateTreatment, Expr, Function,
+    FunctionArg, FunctionArgExpr, FunctionArguments, TrimWhereField, UnaryOperator,
+    Value as SqlValue,
+};
 
+use std::collections::HashMap;
+
+use crate::executor::cte::CteTables;
+use crate::executor::join::Scope;
+use crate::executor::subquery;
 use crate::executor::{table::Table, ExecutionError};
 use crate::model::Value;
+use crate::utils::aggregate;
+use crate::utils::coercion::{self, CastTarget};
+use crate::utils::function::{self, Arity};
+use crate::utils::pattern;
+use crate::utils::window::WindowValues;
+
+/// 表达式求值上下文
+///
+/// 描述表达式求值时可见的数据：当前行所属的表、表在 FROM 子句中的别名或多表连接的列作用域、
+/// 当前行，以及聚合查询中的当前分组；执行子查询所需的表集合、公共表表达式和外层查询的上下文也通过它传递。
+/// 无表查询（如 `SELECT 1 + 1`）使用默认的空上下文。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EvalContext<'a> {
+    /// 可选的表引用，用于解析列名
+    pub table: Option<&'a Table>,
+    /// 表的别名，指定后限定列名只能使用别名
+    pub alias: Option<&'a str>,
+    /// 多表连接结果的列作用域，存在时通过它解析列引用
+    pub scope: Option<&'a Scope>,
+    /// 可选的当前行数据，用于获取列值
+    pub row: Option<&'a [Value]>,
+    /// 当前行在表中的索引，用于查找窗口函数在当前行上的值
+    pub row_index: Option<usize>,
+    /// 当前分组包含的行索引，存在时可以求值聚合函数
+    pub group: Option<&'a [usize]>,
+    /// `WITH ROLLUP` 汇总行中被汇总掉的分组表达式，这些表达式在聚合函数之外求值为 NULL
+    pub rolled_up: &'a [Expr],
+    /// 已加锁的表集合，存在时可以执行子查询
+    pub catalog: Option<&'a HashMap<String, Table>>,
+    /// `WITH` 子句物化出的公共表表达式，解析表名时优先于表集合中的表
+    pub ctes: Option<&'a CteTables<'a>>,
+    /// 外层查询的上下文，相关子查询通过它解析外层的列
+    pub outer: Option<&'a EvalContext<'a>>,
+    /// 查询中窗口函数的计算结果
+    pub windows: Option<&'a WindowValues>,
+}
+
+impl<'a> EvalContext<'a> {
+    /// 创建求值上下文
+    ///
+    /// # Arguments
+    /// * `table` - 可选的表引用
+    /// * `row` - 可选的当前行数据
+    pub fn new(table: Option<&'a Table>, row: Option<&'a [Value]>) -> Self {
+        Self {
+            table,
+            row,
+            ..Default::default()
+        }
+    }
+
+    /// 设置表的别名
+    pub fn with_alias(self, alias: Option<&'a str>) -> Self {
+        Self { alias, ..self }
+    }
+
+    /// 设置多表连接结果的列作用域
+    pub fn with_scope(self, scope: &'a Scope) -> Self {
+        Self {
+            scope: Some(scope),
+            ..self
+        }
+    }
+
+    /// 设置可供子查询访问的表集合
+    pub fn with_catalog(self, catalog: &'a HashMap<String, Table>) -> Self {
+        Self {
+            catalog: Some(catalog),
+            ..self
+        }
+    }
+
+    /// 设置当前可见的公共表表达式
+    pub fn with_ctes(self, ctes: &'a CteTables<'a>) -> Self {
+        Self {
+            ctes: Some(ctes),
+            ..self
+        }
+    }
+
+    /// 设置外层查询的上下文
+    pub fn with_outer(self, outer: Option<&'a EvalContext<'a>>) -> Self {
+        Self { outer, ..self }
+    }
+
+    /// 设置聚合时的当前分组
+    ///
+    /// 分组内的第一行同时作为当前行，用于求值非聚合的列引用；
+    /// 分组为空时当前行为 None，此时非聚合的列引用求值为 NULL。
+    pub fn with_group(self, group: &'a [usize]) -> Self {
+        let row = match (self.table, group.first()) {
+            (Some(table), Some(&idx)) => Some(table.data[idx].as_slice()),
+            _ => None,
+        };
+        Self {
+            group: Some(group),
+            row,
+            ..self
+        }
+    }
+
+    /// 设置 `WITH ROLLUP` 汇总行中被汇总掉的分组表达式
+    pub fn with_rolled_up(self, rolled_up: &'a [Expr]) -> Self {
+        Self { rolled_up, ..self }
+    }
+
+    /// 设置窗口函数的计算结果
+    pub fn with_windows(self, windows: &'a WindowValues) -> Self {
+        Self {
+            windows: Some(windows),
+            ..self
+        }
+    }
+
+    /// 返回切换到另一行数据的上下文
+    pub fn with_row<'b>(&self, row: &'b [Value]) -> EvalContext<'b>
+    where
+        'a: 'b,
+    {
+        EvalContext {
+            row: Some(row),
+            row_index: None,
+            ..*self
+        }
+    }
+
+    /// 返回切换到表中第 `idx` 行的上下文
+    ///
+    /// 与 [`EvalContext::with_row`] 不同，该上下文记录了行索引，可以求值窗口函数。
+    pub fn with_row_at(&self, idx: usize) -> Self {
+        Self {
+            row: self.table.map(|table| table.data[idx].as_slice()),
+            row_index: Some(idx),
+            ..*self
+        }
+    }
+}
 
 /// 表达式求值器
 ///
@@ -36,125 +182,831 @@ impl ExprEvaluator {
         expr: &Expr,
         row: Option<&[Value]>,
     ) -> Result<Value, ExecutionError> {
+        Self::evaluate(&EvalContext::new(table, row), expr)
+    }
+
+    /// 在给定上下文中评估表达式并返回结果
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文，提供表、别名和当前行
+    /// * `expr` - 要评估的表达式
+    ///
+    /// # Returns
+    /// * `Ok(Value)` - 评估结果
+    /// * `Err(ExecutionError)` - 评估错误
+    pub fn evaluate(ctx: &EvalContext, expr: &Expr) -> Result<Value, ExecutionError> {
+        if ctx.group.is_some() && ctx.rolled_up.contains(expr) {
+            return Ok(Value::Null);
+        }
         match expr {
             Expr::Identifier(ident) => {
                 if ident.quote_style.is_some() {
                     Ok(Value::Varchar(ident.value.clone()))
                 } else {
-                    let column_name = ident.value.clone();
-                    if let (Some(table), Some(row)) = (table, row) {
-                        if let Some(column_index) =
-                            table.columns.iter().position(|col| col.name == column_name)
-                        {
-                            return Ok(row[column_index].clone());
-                        } else {
-                            return Err(ExecutionError::ExecutionError(format!(
-                                "列 '{}' 在表 '{}' 中不存在",
-                                column_name, table.name
-                            )));
-                        }
-                    } else {
-                        return Err(ExecutionError::ExecutionError(
-                            "无法在无表环境下解析列标识符".to_string(),
-                        ));
-                    }
+                    Self::resolve_column(ctx, None, &ident.value)
                 }
             }
-            Expr::BinaryOp { left, op, right } => {
-                let left_value = Self::evaluate_expr(table, left, row)?;
-                let right_value = Self::evaluate_expr(table, right, row)?;
-                macro_rules! numeric_binop {
-                    ($lhs:expr, $rhs:expr, $op:tt) => {
-                        match ($lhs, $rhs) {
-                            (Value::Null, _) => return Ok(Value::Null),
-                            (_, Value::Null) => return Ok(Value::Null),
-                            (Value::Int(l), Value::Int(r)) => Ok(Value::Int(l $op r)),
-                            _ => return Err(ExecutionError::ExecutionError(
-                                "不匹配的操作数类型".to_string()
-                            ))
-                        }
-                    }
-                }
-                macro_rules! relop_binop {
-                    ($lhs:expr, $rhs:expr, $op:tt) => {
-                        match ($lhs, $rhs) {
-                            (Value::Null, _) => return Ok(Value::Null),
-                            (_, Value::Null) => return Ok(Value::Null),
-                            (Value::Int(l), Value::Int(r)) => Ok(Value::Bool(l $op r)),
-                            (Value::Varchar(l), Value::Varchar(r)) => Ok(Value::Bool(l $op r)),
-                            _ => return Err(ExecutionError::ExecutionError(
-                                "不匹配的操作数类型".to_string()
-                            ))
-                        }
-                    };
-                }
-                macro_rules! bool_binop {
-                    ($lhs:expr, $rhs:expr, $op:tt) => {
-                        match ($lhs, $rhs) {
-                            // 简化版本，含 NULL 则返回 NULL
-                            (Value::Bool(l), Value::Bool(r)) => Ok(Value::Bool(if l $op r { true } else { false })),
-                            (Value::Null, _) => return Ok(Value::Null),
-                            (_, Value::Null) => return Ok(Value::Null),
-                            _ => return Err(ExecutionError::ExecutionError(
-                                "不匹配的操作数类型".to_string()
-                            ))
-                        }
-                    }
+            Expr::Function(Function { over: Some(_), .. }) => ctx
+                .windows
+                .zip(ctx.row_index)
+                .and_then(|(windows, idx)| windows.get(expr, idx))
+                .cloned()
+                .ok_or(ExecutionError::ExecutionError(format!(
+                    "窗口函数 {} 只能用于 SELECT 列表和 ORDER BY",
+                    expr
+                ))),
+            Expr::Function(function) if aggregate::is_aggregate_call(function) => {
+                Self::evaluate_aggregate(ctx, function)
+            }
+            Expr::Function(function) => Self::evaluate_function(ctx, function),
+            Expr::CompoundIdentifier(idents) => match idents.as_slice() {
+                [qualifier, column] => {
+                    Self::resolve_column(ctx, Some(&qualifier.value), &column.value)
                 }
-                match op {
-                    BinOp::Plus => numeric_binop!(left_value, right_value, +),
-                    BinOp::Minus => numeric_binop!(left_value, right_value, -),
-                    BinOp::Multiply => numeric_binop!(left_value, right_value, *),
-                    BinOp::Divide => {
-                        if let Value::Int(0) = right_value {
-                            return Err(ExecutionError::ExecutionError("除数不能为零".to_string()));
-                        }
-                        numeric_binop!(left_value, right_value, /)
-                    }
-                    BinOp::Eq => relop_binop!(left_value, right_value, ==),
-                    BinOp::NotEq => relop_binop!(left_value, right_value, !=),
-                    BinOp::Gt => relop_binop!(left_value, right_value, >),
-                    BinOp::Lt => relop_binop!(left_value, right_value, <),
-                    BinOp::GtEq => relop_binop!(left_value, right_value, >=),
-                    BinOp::LtEq => relop_binop!(left_value, right_value, <=),
-                    BinOp::And => bool_binop!(left_value, right_value, &&),
-                    BinOp::Or => bool_binop!(left_value, right_value, ||),
-                    _ => {
-                        return Err(ExecutionError::ExecutionError(format!(
-                            "不支持的二元操作符 {}",
-                            op.to_string()
-                        )))
+                _ => Err(ExecutionError::ExecutionError(format!(
+                    "不支持的限定列名 {}",
+                    expr
+                ))),
+            },
+            Expr::Nested(expr) => Self::evaluate(ctx, expr),
+            // `SUBSTRING(s FROM pos FOR len)` 和 `SUBSTRING(s, pos, len)` 都被解析为专门的表达式
+            Expr::Substring {
+                expr,
+                substring_from,
+                substring_for,
+                ..
+            } => {
+                let args = std::iter::once(&**expr)
+                    .chain(substring_from.as_deref())
+                    .chain(substring_for.as_deref())
+                    .map(|arg| Self::evaluate(ctx, arg))
+                    .collect::<Result<Vec<Value>, ExecutionError>>()?;
+                function::call("SUBSTRING", &args)
+            }
+            Expr::Cast {
+                expr, data_type, ..
+            }
+            | Expr::Convert {
+                expr,
+                data_type: Some(data_type),
+                ..
+            } => coercion::cast(
+                Self::evaluate(ctx, expr)?,
+                CastTarget::from_data_type(data_type)?,
+            ),
+            // `CONVERT(x USING charset)` 只转换字符集，结果为字符串
+            Expr::Convert {
+                expr,
+                data_type: None,
+                ..
+            } => coercion::cast(Self::evaluate(ctx, expr)?, CastTarget::Char(None)),
+            Expr::Ceil {
+                expr,
+                field: CeilFloorKind::DateTimeField(DateTimeField::NoDateTime),
+            } => function::call("CEIL", &[Self::evaluate(ctx, expr)?]),
+            Expr::Floor {
+                expr,
+                field: CeilFloorKind::DateTimeField(DateTimeField::NoDateTime),
+            } => function::call("FLOOR", &[Self::evaluate(ctx, expr)?]),
+            Expr::Trim {
+                expr,
+                trim_where,
+                trim_what,
+                trim_characters: None,
+            } => {
+                let value = Self::evaluate(ctx, expr)?;
+                let remove = trim_what
+                    .as_deref()
+                    .map(|what| Self::evaluate(ctx, what))
+                    .transpose()?;
+                let (leading, trailing) = match trim_where {
+                    Some(TrimWhereField::Leading) => (true, false),
+                    Some(TrimWhereField::Trailing) => (false, true),
+                    Some(TrimWhereField::Both) | None => (true, true),
+                };
+                function::trim(&value, remove.as_ref(), leading, trailing)
+            }
+            Expr::UnaryOp { op, expr } => {
+                // 负号并入数值字面量后再解析，否则 -9223372036854775808 的正数部分会超出范围
+                if let (UnaryOperator::Minus, Expr::Value(value)) = (op, expr.as_ref()) {
+                    if let SqlValue::Number(n, _) = &value.value {
+                        return Self::parse_number(&format!("-{}", n));
                     }
                 }
+                let value = Self::evaluate(ctx, expr)?;
+                Self::unary_op(op, value)
+            }
+            Expr::BinaryOp {
+                left,
+                op: op @ (BinOp::And | BinOp::Or),
+                right,
+            } => Self::evaluate_logical(ctx, op, left, right),
+            Expr::BinaryOp { left, op, right } => {
+                let left_value = Self::evaluate(ctx, left)?;
+                let right_value = Self::evaluate(ctx, right)?;
+                Self::binary_op(op, left_value, right_value)
             }
             Expr::Value(value) => match &value.value {
                 SqlValue::SingleQuotedString(s) => Ok(Value::Varchar(s.clone())),
                 SqlValue::DoubleQuotedString(s) => Ok(Value::Varchar(s.clone())),
-                SqlValue::Number(n, _) => Ok(Value::Int(n.parse::<i64>().unwrap())),
-                SqlValue::Boolean(b) => Ok(Value::Bool(b.clone())),
+                SqlValue::Number(n, _) => Self::parse_number(n),
+                SqlValue::Boolean(b) => Ok(Value::Bool(*b)),
                 SqlValue::Null => Ok(Value::Null),
                 _ => Ok(Value::Varchar(value.to_string())),
             },
+            Expr::Subquery(query) => {
+                let result = subquery::execute_subquery(ctx, query)?;
+                Self::check_single_column(&result.columns)?;
+                match result.rows.as_slice() {
+                    [] => Ok(Value::Null),
+                    [row] => Ok(row[0].clone()),
+                    _ => Err(ExecutionError::ExecutionError(
+                        "标量子查询返回了多于一行".to_string(),
+                    )),
+                }
+            }
+            Expr::Exists { subquery, negated } => {
+                let result = subquery::execute_subquery(ctx, subquery)?;
+                Ok(Value::Bool(result.rows.is_empty() == *negated))
+            }
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                let value = Self::evaluate(ctx, expr)?;
+                let result = subquery::execute_subquery(ctx, subquery)?;
+                Self::check_single_column(&result.columns)?;
+                let found = Self::in_values(value, result.rows.iter().map(|row| &row[0]))?;
+                Ok(Self::negate(found, *negated))
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let value = Self::evaluate(ctx, expr)?;
+                let candidates = list
+                    .iter()
+                    .map(|item| Self::evaluate(ctx, item))
+                    .collect::<Result<Vec<Value>, ExecutionError>>()?;
+                let found = Self::in_values(value, candidates.iter())?;
+                Ok(Self::negate(found, *negated))
+            }
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let value = Self::evaluate(ctx, expr)?;
+                let low = Self::evaluate(ctx, low)?;
+                let high = Self::evaluate(ctx, high)?;
+                let above_low = Self::binary_op(&BinOp::GtEq, value.clone(), low)?;
+                let below_high = Self::binary_op(&BinOp::LtEq, value, high)?;
+                // 任一边界比较为 FALSE 时结果为 FALSE，否则含 NULL 时为 NULL
+                let within = match (above_low, below_high) {
+                    (Value::Bool(false), _) | (_, Value::Bool(false)) => Value::Bool(false),
+                    (Value::Bool(true), Value::Bool(true)) => Value::Bool(true),
+                    _ => Value::Null,
+                };
+                Ok(Self::negate(within, *negated))
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                else_result,
+            } => {
+                let operand = match operand {
+                    Some(operand) => Some(Self::evaluate(ctx, operand)?),
+                    None => None,
+                };
+                for case_when in conditions {
+                    let condition = Self::evaluate(ctx, &case_when.condition)?;
+                    let matched = match &operand {
+                        // 简单 CASE：操作数与 WHEN 的值相等时匹配，NULL 不与任何值相等
+                        Some(operand) => Self::binary_op(&BinOp::Eq, operand.clone(), condition)?,
+                        None => condition,
+                    };
+                    if matched == Value::Bool(true) {
+                        return Self::evaluate(ctx, &case_when.result);
+                    }
+                }
+                match else_result {
+                    Some(else_result) => Self::evaluate(ctx, else_result),
+                    None => Ok(Value::Null),
+                }
+            }
+            Expr::Like {
+                negated,
+                any: false,
+                expr,
+                pattern,
+                escape_char,
+            } => Self::evaluate_like(ctx, expr, pattern, escape_char.as_deref(), *negated, false),
+            Expr::ILike {
+                negated,
+                any: false,
+                expr,
+                pattern,
+                escape_char,
+            } => Self::evaluate_like(ctx, expr, pattern, escape_char.as_deref(), *negated, true),
+            Expr::RLike {
+                negated,
+                expr,
+                pattern,
+                ..
+            } => {
+                let value = Self::evaluate(ctx, expr)?;
+                let pattern = Self::evaluate(ctx, pattern)?;
+                Ok(Self::negate(pattern::regexp(&value, &pattern)?, *negated))
+            }
             Expr::IsNull(expr) => {
-                let value = Self::evaluate_expr(table, expr, row)?;
+                let value = Self::evaluate(ctx, expr)?;
                 match value {
                     Value::Null => Ok(Value::Bool(true)),
                     _ => Ok(Value::Bool(false)),
                 }
             }
             Expr::IsNotNull(expr) => {
-                let value = Self::evaluate_expr(table, expr, row)?;
+                let value = Self::evaluate(ctx, expr)?;
                 match value {
                     Value::Null => Ok(Value::Bool(false)),
                     _ => Ok(Value::Bool(true)),
                 }
             }
+            _ => Err(ExecutionError::ExecutionError(format!(
+                "不支持的表达式 {}",
+                expr
+            ))),
+        }
+    }
+
+    /// 判断值是否在一组值中
+    ///
+    /// 遵循 SQL 的三值逻辑：存在相等的值时为 TRUE；否则只要被查找的值或任一候选值为 NULL，
+    /// 结果为 NULL；其余情况为 FALSE。候选值为空时结果总是 FALSE。
+    ///
+    /// # Arguments
+    /// * `value` - 被查找的值
+    /// * `candidates` - 候选值
+    fn in_values<'v>(
+        value: Value,
+        candidates: impl Iterator<Item = &'v Value>,
+    ) -> Result<Value, ExecutionError> {
+        let mut result = Value::Bool(false);
+        for candidate in candidates {
+            match Self::binary_op(&BinOp::Eq, value.clone(), candidate.clone())? {
+                Value::Bool(true) => return Ok(Value::Bool(true)),
+                Value::Null => result = Value::Null,
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+
+    /// 求值 `LIKE` 和 `ILIKE` 表达式
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文
+    /// * `expr` - 被匹配的表达式
+    /// * `pattern` - 模式表达式
+    /// * `escape` - `ESCAPE` 子句指定的转义字符
+    /// * `negated` - 是否为 `NOT LIKE`
+    /// * `case_insensitive` - 是否忽略大小写
+    fn evaluate_like(
+        ctx: &EvalContext,
+        expr: &Expr,
+        pattern: &Expr,
+        escape: Option<&str>,
+        negated: bool,
+        case_insensitive: bool,
+    ) -> Result<Value, ExecutionError> {
+        let value = Self::evaluate(ctx, expr)?;
+        let pattern = Self::evaluate(ctx, pattern)?;
+        Ok(Self::negate(
+            pattern::like(&value, &pattern, escape, case_insensitive)?,
+            negated,
+        ))
+    }
+
+    /// 按需对逻辑值取反，NULL 保持不变
+    fn negate(value: Value, negated: bool) -> Value {
+        match value {
+            Value::Bool(b) => Value::Bool(b != negated),
+            other => other,
+        }
+    }
+
+    /// 检查子查询结果只有一列
+    fn check_single_column(columns: &[String]) -> Result<(), ExecutionError> {
+        if columns.len() != 1 {
+            return Err(ExecutionError::ExecutionError(format!(
+                "子查询只能返回一列，实际返回了 {} 列",
+                columns.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 求值标量函数调用
+    ///
+    /// `IF`、`IFNULL` 和 `COALESCE` 按需求值参数，未被选中的分支不会求值；
+    /// 其余函数先求值全部参数，再交给标量函数注册表。
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文
+    /// * `function` - 函数调用表达式
+    fn evaluate_function(ctx: &EvalContext, function: &Function) -> Result<Value, ExecutionError> {
+        let name = function.name.to_string().to_uppercase();
+        let args = match &function.args {
+            FunctionArguments::List(arg_list) => arg_list
+                .args
+                .iter()
+                .map(|arg| match arg {
+                    FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Ok(expr),
+                    _ => Err(ExecutionError::ExecutionError(format!(
+                        "函数 {} 的参数无效",
+                        name
+                    ))),
+                })
+                .collect::<Result<Vec<&Expr>, ExecutionError>>()?,
+            _ => vec![],
+        };
+        match name.as_str() {
+            "IF" => {
+                function::check_arity(&name, Arity::Exact(3), args.len())?;
+                let condition = Self::evaluate(ctx, args[0])?;
+                // 条件为 NULL 时视为不成立
+                if Self::truth_value(&condition)? == Some(true) {
+                    Self::evaluate(ctx, args[1])
+                } else {
+                    Self::evaluate(ctx, args[2])
+                }
+            }
+            "IFNULL" | "COALESCE" => {
+                let arity = if name == "IFNULL" {
+                    Arity::Exact(2)
+                } else {
+                    Arity::AtLeast(1)
+                };
+                function::check_arity(&name, arity, args.len())?;
+                for arg in args {
+                    let value = Self::evaluate(ctx, arg)?;
+                    if !matches!(value, Value::Null) {
+                        return Ok(value);
+                    }
+                }
+                Ok(Value::Null)
+            }
+            _ => {
+                let values = args
+                    .into_iter()
+                    .map(|arg| Self::evaluate(ctx, arg))
+                    .collect::<Result<Vec<Value>, ExecutionError>>()?;
+                function::call(&name, &values)
+            }
+        }
+    }
+
+    /// 解析数值字面量
+    ///
+    /// 不含小数点和指数的字面量解析为整数，其余解析为浮点数。
+    ///
+    /// # Arguments
+    /// * `literal` - 数值字面量的文本
+    ///
+    /// # Returns
+    /// * `Ok(Value)` - 整数或浮点数
+    /// * `Err(ExecutionError)` - 整数超出 64 位有符号整数的范围，或字面量无效
+    pub fn parse_number(literal: &str) -> Result<Value, ExecutionError> {
+        if literal.contains(['.', 'e', 'E']) {
+            return literal
+                .parse::<f64>()
+                .map(Value::Float)
+                .map_err(|_| ExecutionError::ExecutionError(format!("无效的数值 {}", literal)));
+        }
+        literal.parse::<i64>().map(Value::Int).map_err(|_| {
+            ExecutionError::ExecutionError(format!("整数 {} 超出 BIGINT 的范围", literal))
+        })
+    }
+
+    /// 短路求值 `AND` 和 `OR`
+    ///
+    /// 左侧已经决定结果时（`AND` 左侧为 FALSE 或 `OR` 左侧为 TRUE）不再求值右侧，
+    /// 因此右侧即使会出错也不影响结果。
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文
+    /// * `op` - `AND` 或 `OR`
+    /// * `left` - 左侧表达式
+    /// * `right` - 右侧表达式
+    fn evaluate_logical(
+        ctx: &EvalContext,
+        op: &BinOp,
+        left: &Expr,
+        right: &Expr,
+    ) -> Result<Value, ExecutionError> {
+        let dominant = matches!(op, BinOp::Or);
+        let left_value = Self::evaluate(ctx, left)?;
+        if Self::truth_value(&left_value)? == Some(dominant) {
+            return Ok(Value::Bool(dominant));
+        }
+        let right_value = Self::evaluate(ctx, right)?;
+        Self::binary_op(op, left_value, right_value)
+    }
+
+    /// 将位运算的操作数转换为整数，布尔值视为 `1`/`0`，NULL 返回 None
+    fn bitwise_operand(value: &Value) -> Result<Option<i64>, ExecutionError> {
+        match value {
+            Value::Int(i) => Ok(Some(*i)),
+            Value::Bool(b) => Ok(Some(i64::from(*b))),
+            Value::Null => Ok(None),
+            _ => Err(ExecutionError::ExecutionError(format!(
+                "位运算的操作数必须是整数，实际为 {}",
+                value
+            ))),
+        }
+    }
+
+    /// 将逻辑运算的操作数转换为真值，与 MySQL 一致非零整数视为真，NULL 返回 None
+    fn truth_value(value: &Value) -> Result<Option<bool>, ExecutionError> {
+        match value {
+            Value::Bool(b) => Ok(Some(*b)),
+            Value::Int(i) => Ok(Some(*i != 0)),
+            Value::Null => Ok(None),
+            _ => Err(ExecutionError::ExecutionError(format!(
+                "逻辑运算的操作数必须是布尔值，实际为 {}",
+                value
+            ))),
+        }
+    }
+
+    /// 对已求值的操作数应用一元操作符
+    ///
+    /// # Arguments
+    /// * `op` - 一元操作符
+    /// * `value` - 操作数的值
+    ///
+    /// # Returns
+    /// * `Ok(Value)` - 运算结果，操作数为 NULL 时为 NULL
+    /// * `Err(ExecutionError)` - 操作数类型不匹配或操作符不受支持
+    fn unary_op(op: &UnaryOperator, value: Value) -> Result<Value, ExecutionError> {
+        let mismatch =
+            || ExecutionError::ExecutionError(format!("不匹配的操作数类型: {}{}", op, value));
+        match (op, &value) {
+            (_, Value::Null) => Ok(Value::Null),
+            (UnaryOperator::Plus, Value::Int(_) | Value::Float(_)) => Ok(value),
+            (UnaryOperator::Minus, Value::Int(i)) => {
+                i.checked_neg()
+                    .map(Value::Int)
+                    .ok_or(ExecutionError::ExecutionError(format!(
+                        "整数运算溢出: -{} 超出 BIGINT 的范围",
+                        i
+                    )))
+            }
+            (UnaryOperator::Minus, Value::Float(f)) => Ok(Value::Float(-f)),
+            (UnaryOperator::Minus, Value::Bool(b)) => Ok(Value::Int(-i64::from(*b))),
+            (UnaryOperator::Not, Value::Bool(_) | Value::Int(_)) => {
+                Ok(Self::truth_value(&value)?.map_or(Value::Null, |b| Value::Bool(!b)))
+            }
+            (UnaryOperator::PGBitwiseNot, Value::Int(i)) => Ok(Value::Int(!i)),
+            (UnaryOperator::PGBitwiseNot, Value::Bool(b)) => Ok(Value::Int(!i64::from(*b))),
+            (
+                UnaryOperator::Plus
+                | UnaryOperator::Minus
+                | UnaryOperator::Not
+                | UnaryOperator::PGBitwiseNot,
+                _,
+            ) => Err(mismatch()),
+            _ => Err(ExecutionError::ExecutionError(format!(
+                "不支持的一元操作符 {}",
+                op
+            ))),
+        }
+    }
+
+    /// 对两个已求值的操作数应用二元操作符
+    ///
+    /// # Arguments
+    /// * `op` - 二元操作符
+    /// * `left_value` - 左操作数的值
+    /// * `right_value` - 右操作数的值
+    ///
+    /// # Returns
+    /// * `Ok(Value)` - 运算结果，任一操作数为 NULL 时通常为 NULL
+    /// * `Err(ExecutionError)` - 操作数类型不匹配或操作符不受支持
+    pub(crate) fn binary_op(
+        op: &BinOp,
+        left_value: Value,
+        right_value: Value,
+    ) -> Result<Value, ExecutionError> {
+        macro_rules! numeric_binop {
+            ($lhs:expr, $rhs:expr, $op:tt, $checked:ident) => {
+                match ($lhs, $rhs) {
+                    (Value::Null, _) => return Ok(Value::Null),
+                    (_, Value::Null) => return Ok(Value::Null),
+                    (Value::Int(l), Value::Int(r)) => l.$checked(r).map(Value::Int).ok_or(
+                        ExecutionError::ExecutionError(format!(
+                            "整数运算溢出: {} {} {} 超出 BIGINT 的范围",
+                            l, stringify!($op), r
+                        ))
+                    ),
+                    (Value::Int(l), Value::Float(r)) => Ok(Value::Float(l as f64 $op r)),
+                    (Value::Float(l), Value::Int(r)) => Ok(Value::Float(l $op r as f64)),
+                    (Value::Float(l), Value::Float(r)) => Ok(Value::Float(l $op r)),
+                    _ => return Err(ExecutionError::ExecutionError(
+                        "不匹配的操作数类型".to_string()
+                    ))
+                }
+            }
+        }
+        macro_rules! relop_binop {
+            ($lhs:expr, $rhs:expr, $op:tt) => {
+                match ($lhs, $rhs) {
+                    (Value::Null, _) => return Ok(Value::Null),
+                    (_, Value::Null) => return Ok(Value::Null),
+                    (Value::Int(l), Value::Int(r)) => Ok(Value::Bool(l $op r)),
+                    (Value::Int(l), Value::Float(r)) => Ok(Value::Bool((l as f64) $op r)),
+                    (Value::Float(l), Value::Int(r)) => Ok(Value::Bool(l $op (r as f64))),
+                    (Value::Float(l), Value::Float(r)) => Ok(Value::Bool(l $op r)),
+                    (Value::Varchar(l), Value::Varchar(r)) => Ok(Value::Bool(l $op r)),
+                    // 与 MySQL 一致，FALSE 小于 TRUE
+                    (Value::Bool(l), Value::Bool(r)) => Ok(Value::Bool(l $op r)),
+                    _ => return Err(ExecutionError::ExecutionError(
+                        "不匹配的操作数类型".to_string()
+                    ))
+                }
+            };
+        }
+        macro_rules! bitwise_binop {
+            ($lhs:expr, $rhs:expr, |$l:ident: i64, $r:ident: i64| $body:expr) => {
+                match (Self::bitwise_operand(&$lhs)?, Self::bitwise_operand(&$rhs)?) {
+                    (Some($l), Some($r)) => Ok(Value::Int($body)),
+                    _ => Ok(Value::Null),
+                }
+            };
+            ($lhs:expr, $rhs:expr, $op:tt) => {
+                bitwise_binop!($lhs, $rhs, |l: i64, r: i64| l $op r)
+            };
+        }
+        let is_arithmetic = matches!(
+            op,
+            BinOp::Plus
+                | BinOp::Minus
+                | BinOp::Multiply
+                | BinOp::Divide
+                | BinOp::Modulo
+                | BinOp::MyIntegerDivide
+        );
+        let is_comparison = matches!(
+            op,
+            BinOp::Eq | BinOp::NotEq | BinOp::Gt | BinOp::Lt | BinOp::GtEq | BinOp::LtEq
+        );
+        let (left_value, right_value) = if is_arithmetic || is_comparison {
+            coercion::coerce_operands(left_value, right_value, is_arithmetic)?
+        } else {
+            (left_value, right_value)
+        };
+        match op {
+            BinOp::Plus => numeric_binop!(left_value, right_value, +, checked_add),
+            BinOp::Minus => numeric_binop!(left_value, right_value, -, checked_sub),
+            BinOp::Multiply => numeric_binop!(left_value, right_value, *, checked_mul),
+            BinOp::Divide => {
+                if matches!(right_value, Value::Int(0))
+                    || matches!(right_value, Value::Float(f) if f == 0.0)
+                {
+                    return Err(ExecutionError::ExecutionError("除数不能为零".to_string()));
+                }
+                numeric_binop!(left_value, right_value, /, checked_div)
+            }
+            BinOp::Modulo | BinOp::MyIntegerDivide
+                if matches!(right_value, Value::Int(0))
+                    || matches!(right_value, Value::Float(f) if f == 0.0) =>
+            {
+                Err(ExecutionError::ExecutionError("除数不能为零".to_string()))
+            }
+            BinOp::Modulo => numeric_binop!(left_value, right_value, %, checked_rem),
+            BinOp::MyIntegerDivide => match (left_value, right_value) {
+                (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+                (Value::Int(l), Value::Int(r)) => {
+                    l.checked_div(r)
+                        .map(Value::Int)
+                        .ok_or(ExecutionError::ExecutionError(format!(
+                            "整数运算溢出: {} DIV {} 超出 BIGINT 的范围",
+                            l, r
+                        )))
+                }
+                (l, r) => match numeric_binop!(l, r, /, checked_div)? {
+                    Value::Float(f) => Ok(Value::Int(f.trunc() as i64)),
+                    other => Ok(other),
+                },
+            },
+            BinOp::BitwiseAnd => bitwise_binop!(left_value, right_value, &),
+            BinOp::BitwiseOr => bitwise_binop!(left_value, right_value, |),
+            BinOp::BitwiseXor => bitwise_binop!(left_value, right_value, ^),
+            // 位移量为负数或不小于 64 时结果为 0
+            BinOp::PGBitwiseShiftLeft => {
+                bitwise_binop!(left_value, right_value, |l: i64, r: i64| {
+                    u32::try_from(r)
+                        .ok()
+                        .and_then(|r| l.checked_shl(r))
+                        .unwrap_or(0)
+                })
+            }
+            BinOp::PGBitwiseShiftRight => {
+                bitwise_binop!(left_value, right_value, |l: i64, r: i64| {
+                    u32::try_from(r)
+                        .ok()
+                        .and_then(|r| l.checked_shr(r))
+                        .unwrap_or(0)
+                })
+            }
+            BinOp::StringConcat => Ok(match (left_value.to_text(), right_value.to_text()) {
+                (Some(l), Some(r)) => Value::Varchar(l + &r),
+                _ => Value::Null,
+            }),
+            BinOp::Xor => match (
+                Self::truth_value(&left_value)?,
+                Self::truth_value(&right_value)?,
+            ) {
+                (Some(l), Some(r)) => Ok(Value::Bool(l != r)),
+                _ => Ok(Value::Null),
+            },
+            BinOp::Eq => relop_binop!(left_value, right_value, ==),
+            BinOp::NotEq => relop_binop!(left_value, right_value, !=),
+            BinOp::Gt => relop_binop!(left_value, right_value, >),
+            BinOp::Lt => relop_binop!(left_value, right_value, <),
+            BinOp::GtEq => relop_binop!(left_value, right_value, >=),
+            BinOp::LtEq => relop_binop!(left_value, right_value, <=),
+            // Kleene 三值逻辑：AND 中的 FALSE 和 OR 中的 TRUE 决定结果，不论另一侧是否为 NULL
+            BinOp::And | BinOp::Or => {
+                let dominant = matches!(op, BinOp::Or);
+                match (
+                    Self::truth_value(&left_value)?,
+                    Self::truth_value(&right_value)?,
+                ) {
+                    (Some(l), _) if l == dominant => Ok(Value::Bool(dominant)),
+                    (_, Some(r)) if r == dominant => Ok(Value::Bool(dominant)),
+                    (Some(_), Some(_)) => Ok(Value::Bool(!dominant)),
+                    _ => Ok(Value::Null),
+                }
+            }
+            _ => Err(ExecutionError::ExecutionError(format!(
+                "不支持的二元操作符 {}",
+                op
+            ))),
+        }
+    }
+
+    /// 求值聚合函数
+    ///
+    /// 对当前分组中的每一行求值聚合函数的参数并交给累加器。
+    /// `COUNT(*)` 统计分组的行数；其余聚合函数忽略 NULL 参数值。
+    /// 无表查询（如 `SELECT COUNT(*)`）的分组中每个元素对应一个没有列的虚拟行。
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文，必须包含当前分组
+    /// * `function` - 聚合函数调用
+    fn evaluate_aggregate(ctx: &EvalContext, function: &Function) -> Result<Value, ExecutionError> {
+        let Some(group) = ctx.group else {
+            return Err(ExecutionError::ExecutionError(format!(
+                "聚合函数 {} 只能用于 SELECT 列表",
+                function.name
+            )));
+        };
+        let name = function.name.to_string().to_uppercase();
+        let FunctionArguments::List(arg_list) = &function.args else {
+            return Err(ExecutionError::ExecutionError(format!(
+                "聚合函数 {} 需要一个参数",
+                name
+            )));
+        };
+        let arg = match arg_list.args.as_slice() {
+            [FunctionArg::Unnamed(FunctionArgExpr::Wildcard)] if name == "COUNT" => None,
+            [FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))] => Some(expr),
             _ => {
                 return Err(ExecutionError::ExecutionError(format!(
-                    "不支持的表达式 {}",
-                    expr.to_string()
+                    "聚合函数 {} 需要一个参数",
+                    name
                 )))
             }
+        };
+        let is_distinct = matches!(
+            arg_list.duplicate_treatment,
+            Some(DuplicateTreatment::Distinct)
+        );
+        let mut accumulator = aggregate::create_accumulator(&name, is_distinct).ok_or(
+            ExecutionError::ExecutionError(format!("不支持的聚合函数 {}", name)),
+        )?;
+
+        for &idx in group {
+            // 聚合函数的参数中不允许再嵌套聚合函数，因此去掉分组信息
+            let row_ctx = EvalContext {
+                row: ctx.table.map(|table| table.data[idx].as_slice()),
+                group: None,
+                ..*ctx
+            };
+            let value = match arg {
+                Some(expr) => Self::evaluate(&row_ctx, expr)?,
+                None => Value::Int(1),
+            };
+            accumulator.step(&value)?;
+        }
+        accumulator.finish()
+    }
+
+    /// 解析列引用并返回当前行中的列值
+    ///
+    /// 列引用无法在当前查询中解析时，依次在外层查询中解析（相关子查询）；
+    /// 所有外层都无法解析时返回当前查询中的解析错误。
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文
+    /// * `qualifier` - 可选的表名或别名限定
+    /// * `column_name` - 列名
+    fn resolve_column(
+        ctx: &EvalContext,
+        qualifier: Option<&str>,
+        column_name: &str,
+    ) -> Result<Value, ExecutionError> {
+        match Self::lookup_column(ctx, qualifier, column_name) {
+            Err(err) => match ctx.outer {
+                Some(outer) => Self::resolve_column(outer, qualifier, column_name).map_err(|_| err),
+                None => Err(err),
+            },
+            value => value,
+        }
+    }
+
+    /// 判断列引用是否引用外层查询的列
+    ///
+    /// 列引用无法在当前查询中解析、但可以在外层查询中解析时返回 true。
+    /// 外层查询的列在子查询中相当于常量，分组查询的检查需要跳过这些引用。
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文
+    /// * `qualifier` - 可选的表名或别名限定
+    /// * `column_name` - 列名
+    pub fn is_outer_column(ctx: &EvalContext, qualifier: Option<&str>, column_name: &str) -> bool {
+        let is_local = match (ctx.scope, ctx.table) {
+            (Some(scope), _) => scope.resolve(qualifier, column_name).is_ok(),
+            (None, Some(table)) => {
+                qualifier.is_none_or(|qualifier| qualifier == ctx.alias.unwrap_or(&table.name))
+                    && table.get_column_index(column_name).is_some()
+            }
+            (None, None) => false,
+        };
+        !is_local
+            && ctx
+                .outer
+                .is_some_and(|outer| Self::resolve_column(outer, qualifier, column_name).is_ok())
+    }
+
+    /// 在当前查询中解析列引用
+    ///
+    /// 带限定名时，限定名必须与表的别名一致；表没有别名时则必须与表名一致。
+    /// 多表连接时通过列作用域解析，不带限定名的列名必须只属于一张表。
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文
+    /// * `qualifier` - 可选的表名或别名限定
+    /// * `column_name` - 列名
+    fn lookup_column(
+        ctx: &EvalContext,
+        qualifier: Option<&str>,
+        column_name: &str,
+    ) -> Result<Value, ExecutionError> {
+        if ctx.table.is_some() && ctx.row.is_none() && ctx.group.is_some() {
+            // 空分组中非聚合的列引用求值为 NULL
+            return Ok(Value::Null);
+        }
+        if let Some(scope) = ctx.scope {
+            let row = ctx.row.ok_or(ExecutionError::ExecutionError(
+                "无法在无行环境下解析列标识符".to_string(),
+            ))?;
+            let indices = scope.resolve(qualifier, column_name)?;
+            return Ok(Scope::value(row, &indices));
+        }
+        let (Some(table), Some(row)) = (ctx.table, ctx.row) else {
+            return Err(ExecutionError::ExecutionError(
+                "无法在无表环境下解析列标识符".to_string(),
+            ));
+        };
+        if let Some(qualifier) = qualifier {
+            if qualifier != ctx.alias.unwrap_or(&table.name) {
+                return Err(ExecutionError::ExecutionError(format!(
+                    "未知的表限定名 '{}'",
+                    qualifier
+                )));
+            }
+        }
+        match table.get_column_index(column_name) {
+            Some(column_index) => Ok(row[column_index].clone()),
+            None => Err(ExecutionError::ExecutionError(format!(
+                "列 '{}' 在表 '{}' 中不存在",
+                column_name, table.name
+            ))),
         }
     }
 }
@@ -0,0 +1,587 @@
+/// 标量函数模块
+///
+/// 维护内置字符串、数学和比较函数的注册表。表达式求值器对函数调用的参数求值后，按函数名在注册表中查找实现，
+/// 检查参数个数并调用。大部分函数在任一参数为 NULL 时直接返回 NULL，
+/// 参数类型不符合要求时返回指明函数名和参数位置的错误。
+/// 嵌入数据库的程序可以通过 `register_scalar_function` 向注册表中添加自定义函数。
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+use sqlparser::ast::BinaryOperator;
+
+use crate::executor::ExecutionError;
+use crate::model::Value;
+use crate::utils::aggregate;
+use crate::utils::expr_evaluator::ExprEvaluator;
+
+/// 标量函数的实现，接收已求值的参数
+type ScalarImpl = Arc<dyn Fn(&[Value]) -> Result<Value, ExecutionError> + Send + Sync>;
+
+/// 由表达式求值器按需求值参数的函数，不经过注册表
+const SPECIAL_FORMS: [&str; 3] = ["IF", "IFNULL", "COALESCE"];
+
+/// 函数生成的字符串的最大字符数，与 MySQL 默认的 `max_allowed_packet`（64 MB）一致
+const MAX_RESULT_LENGTH: usize = 64 * 1024 * 1024;
+
+/// 函数接受的参数个数
+#[derive(Debug, Clone, Copy)]
+pub enum Arity {
+    /// 固定个数
+    Exact(usize),
+    /// 介于最小值和最大值之间（均包含）
+    Range(usize, usize),
+    /// 不少于指定个数
+    AtLeast(usize),
+}
+
+impl Arity {
+    /// 判断参数个数是否符合要求
+    fn accepts(&self, count: usize) -> bool {
+        match *self {
+            Arity::Exact(n) => count == n,
+            Arity::Range(min, max) => (min..=max).contains(&count),
+            Arity::AtLeast(min) => count >= min,
+        }
+    }
+
+    /// 参数个数要求的文字描述，用于错误信息
+    fn describe(&self) -> String {
+        match *self {
+            Arity::Exact(n) => format!("{} 个", n),
+            Arity::Range(min, max) => format!("{} 到 {} 个", min, max),
+            Arity::AtLeast(min) => format!("至少 {} 个", min),
+        }
+    }
+}
+
+/// 注册表中的一个标量函数
+#[derive(Clone)]
+struct ScalarFunction {
+    /// 参数个数
+    arity: Arity,
+    /// 任一参数为 NULL 时是否直接返回 NULL 而不调用实现
+    propagates_null: bool,
+    /// 是否为内置函数，内置函数不能被用户定义的函数替换
+    is_builtin: bool,
+    /// 函数实现
+    function: ScalarImpl,
+}
+
+lazy_static! {
+    /// 内置和用户定义的标量函数，以大写的函数名为键
+    static ref FUNCTIONS: RwLock<HashMap<String, ScalarFunction>> = {
+        let mut functions = HashMap::new();
+        let mut register = |names: &[&str],
+                            arity,
+                            propagates_null,
+                            function: fn(&[Value]) -> Result<Value, ExecutionError>| {
+            for name in names {
+                functions.insert(
+                    name.to_string(),
+                    ScalarFunction {
+                        arity,
+                        propagates_null,
+                        is_builtin: true,
+                        function: Arc::new(function),
+                    },
+                );
+            }
+        };
+        register(&["CONCAT"], Arity::AtLeast(1), true, concat);
+        register(&["CONCAT_WS"], Arity::AtLeast(2), false, concat_ws);
+        register(&["UPPER", "UCASE"], Arity::Exact(1), true, upper);
+        register(&["LOWER", "LCASE"], Arity::Exact(1), true, lower);
+        register(&["LENGTH", "OCTET_LENGTH"], Arity::Exact(1), true, length);
+        register(&["CHAR_LENGTH", "CHARACTER_LENGTH"], Arity::Exact(1), true, char_length);
+        register(&["SUBSTRING", "SUBSTR"], Arity::Range(2, 3), true, substring);
+        register(&["TRIM"], Arity::Exact(1), true, |args| trim(&args[0], None, true, true));
+        register(&["LTRIM"], Arity::Exact(1), true, |args| trim(&args[0], None, true, false));
+        register(&["RTRIM"], Arity::Exact(1), true, |args| trim(&args[0], None, false, true));
+        register(&["REPLACE"], Arity::Exact(3), true, replace);
+        register(&["LPAD"], Arity::Exact(3), true, |args| pad("LPAD", args, true));
+        register(&["RPAD"], Arity::Exact(3), true, |args| pad("RPAD", args, false));
+        register(&["LEFT"], Arity::Exact(2), true, |args| take("LEFT", args, true));
+        register(&["RIGHT"], Arity::Exact(2), true, |args| take("RIGHT", args, false));
+        register(&["INSTR"], Arity::Exact(2), true, instr);
+        register(&["ABS"], Arity::Exact(1), true, abs);
+        register(&["ROUND"], Arity::Range(1, 2), true, round);
+        register(&["FLOOR"], Arity::Exact(1), true, |args| {
+            round_to_integer("FLOOR", args, f64::floor)
+        });
+        register(&["CEIL", "CEILING"], Arity::Exact(1), true, |args| {
+            round_to_integer("CEIL", args, f64::ceil)
+        });
+        register(&["POWER", "POW"], Arity::Exact(2), true, power);
+        register(&["SQRT"], Arity::Exact(1), true, sqrt);
+        register(&["MOD"], Arity::Exact(2), true, |args| {
+            ExprEvaluator::binary_op(&BinaryOperator::Modulo, args[0].clone(), args[1].clone())
+        });
+        register(&["GREATEST"], Arity::AtLeast(2), true, |args| {
+            extremum(args, BinaryOperator::Gt)
+        });
+        register(&["LEAST"], Arity::AtLeast(2), true, |args| {
+            extremum(args, BinaryOperator::Lt)
+        });
+        register(&["NULLIF"], Arity::Exact(2), false, nullif);
+        RwLock::new(functions)
+    };
+}
+
+/// 注册用户定义的标量函数
+///
+/// 注册后即可在 SQL 中按函数名调用，函数名大小写不敏感。与大部分内置函数不同，
+/// 参数中的 NULL 会原样传给实现，由实现决定结果。再次注册同名的函数时替换之前的实现。
+///
+/// # Arguments
+/// * `name` - 函数名，只能包含字母、数字和下划线
+/// * `arity` - 函数接受的参数个数，调用时由注册表检查
+/// * `function` - 函数实现，接收已求值的参数
+///
+/// # Returns
+/// * `Result<(), ExecutionError>` - 函数名无效，或与内置函数、聚合函数重名时返回错误
+pub fn register_scalar_function<F>(
+    name: &str,
+    arity: Arity,
+    function: F,
+) -> Result<(), ExecutionError>
+where
+    F: Fn(&[Value]) -> Result<Value, ExecutionError> + Send + Sync + 'static,
+{
+    let name = validate_function_name(name)?;
+    if SPECIAL_FORMS.contains(&name.as_str()) || aggregate::is_aggregate_function(&name) {
+        return Err(ExecutionError::ExecutionError(format!(
+            "函数 {} 已存在",
+            name
+        )));
+    }
+    let mut functions = FUNCTIONS.write().unwrap();
+    if functions
+        .get(&name)
+        .is_some_and(|function| function.is_builtin)
+    {
+        return Err(ExecutionError::ExecutionError(format!(
+            "函数 {} 已存在",
+            name
+        )));
+    }
+    functions.insert(
+        name,
+        ScalarFunction {
+            arity,
+            propagates_null: false,
+            is_builtin: false,
+            function: Arc::new(function),
+        },
+    );
+    Ok(())
+}
+
+/// 判断函数名是否为标量函数，包括内置函数和用户定义的函数
+///
+/// # Arguments
+/// * `name` - 函数名，大小写不敏感
+pub fn is_scalar_function(name: &str) -> bool {
+    let name = name.to_uppercase();
+    SPECIAL_FORMS.contains(&name.as_str()) || FUNCTIONS.read().unwrap().contains_key(&name)
+}
+
+/// 检查自定义函数的函数名，返回大写的函数名
+///
+/// # Arguments
+/// * `name` - 函数名
+///
+/// # Returns
+/// * `Result<String, ExecutionError>` - 大写的函数名，函数名为空或包含其他字符时返回错误
+pub fn validate_function_name(name: &str) -> Result<String, ExecutionError> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(ExecutionError::ExecutionError(format!(
+            "无效的函数名 '{}'",
+            name
+        )));
+    }
+    Ok(name.to_uppercase())
+}
+
+/// 调用标量函数
+///
+/// # Arguments
+/// * `name` - 函数名，大小写不敏感
+/// * `args` - 已求值的参数
+///
+/// # Returns
+/// * `Ok(Value)` - 函数结果
+/// * `Err(ExecutionError)` - 函数不存在、参数个数或类型不符合要求
+pub fn call(name: &str, args: &[Value]) -> Result<Value, ExecutionError> {
+    let name = name.to_uppercase();
+    // 调用实现之前释放读锁，避免实现中注册函数时死锁
+    let function = FUNCTIONS
+        .read()
+        .unwrap()
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| ExecutionError::ExecutionError(format!("不支持的函数 {}", name)))?;
+    check_arity(&name, function.arity, args.len())?;
+    if function.propagates_null && args.iter().any(|arg| matches!(arg, Value::Null)) {
+        return Ok(Value::Null);
+    }
+    (function.function)(args)
+}
+
+/// 检查函数调用的参数个数
+///
+/// # Arguments
+/// * `name` - 函数名，用于错误信息
+/// * `arity` - 函数接受的参数个数
+/// * `count` - 实际的参数个数
+pub fn check_arity(name: &str, arity: Arity, count: usize) -> Result<(), ExecutionError> {
+    if arity.accepts(count) {
+        Ok(())
+    } else {
+        Err(ExecutionError::ExecutionError(format!(
+            "函数 {} 需要 {}参数，实际为 {} 个",
+            name,
+            arity.describe(),
+            count
+        )))
+    }
+}
+
+/// 将参数转换为字符串，数值使用其十进制形式
+fn text_arg(args: &[Value], index: usize) -> String {
+    args[index].to_text().unwrap_or_default()
+}
+
+/// 将参数转换为整数，浮点数四舍五入
+///
+/// # Arguments
+/// * `name` - 函数名，用于错误信息
+/// * `args` - 已求值的参数
+/// * `index` - 参数位置，从 0 开始
+fn int_arg(name: &str, args: &[Value], index: usize) -> Result<i64, ExecutionError> {
+    match &args[index] {
+        Value::Int(i) => Ok(*i),
+        Value::Bool(b) => Ok(i64::from(*b)),
+        Value::Float(f) => Ok(f.round() as i64),
+        value => Err(ExecutionError::ExecutionError(format!(
+            "函数 {} 的第 {} 个参数必须是整数，实际为 '{}'",
+            name,
+            index + 1,
+            value
+        ))),
+    }
+}
+
+/// 检查参数是否为数值，布尔值视为 `1`/`0`
+///
+/// # Arguments
+/// * `name` - 函数名，用于错误信息
+/// * `args` - 已求值的参数
+/// * `index` - 参数位置，从 0 开始
+///
+/// # Returns
+/// * `Result<Value, ExecutionError>` - 整数或浮点数
+fn numeric_arg(name: &str, args: &[Value], index: usize) -> Result<Value, ExecutionError> {
+    match &args[index] {
+        Value::Int(_) | Value::Float(_) => Ok(args[index].clone()),
+        Value::Bool(b) => Ok(Value::Int(i64::from(*b))),
+        value => Err(ExecutionError::ExecutionError(format!(
+            "函数 {} 的第 {} 个参数必须是数值，实际为 '{}'",
+            name,
+            index + 1,
+            value
+        ))),
+    }
+}
+
+/// 将数值参数转换为浮点数
+fn float_arg(name: &str, args: &[Value], index: usize) -> Result<f64, ExecutionError> {
+    match numeric_arg(name, args, index)? {
+        Value::Int(i) => Ok(i as f64),
+        Value::Float(f) => Ok(f),
+        _ => unreachable!("numeric_arg 只返回整数或浮点数"),
+    }
+}
+
+/// 取字符串中从第 `start` 个字符（从 0 开始）起的至多 `len` 个字符
+fn char_slice(s: &str, start: usize, len: usize) -> String {
+    s.chars().skip(start).take(len).collect()
+}
+
+/// `CONCAT(s1, s2, ...)`：依次拼接所有参数
+fn concat(args: &[Value]) -> Result<Value, ExecutionError> {
+    Ok(Value::Varchar(
+        (0..args.len()).map(|i| text_arg(args, i)).collect(),
+    ))
+}
+
+/// `CONCAT_WS(sep, s1, s2, ...)`：以分隔符拼接参数，跳过 NULL 参数；分隔符为 NULL 时结果为 NULL
+fn concat_ws(args: &[Value]) -> Result<Value, ExecutionError> {
+    let Some(separator) = args[0].to_text() else {
+        return Ok(Value::Null);
+    };
+    Ok(Value::Varchar(
+        args[1..]
+            .iter()
+            .filter_map(Value::to_text)
+            .collect::<Vec<_>>()
+            .join(&separator),
+    ))
+}
+
+/// `UPPER(s)`：转换为大写
+fn upper(args: &[Value]) -> Result<Value, ExecutionError> {
+    Ok(Value::Varchar(text_arg(args, 0).to_uppercase()))
+}
+
+/// `LOWER(s)`：转换为小写
+fn lower(args: &[Value]) -> Result<Value, ExecutionError> {
+    Ok(Value::Varchar(text_arg(args, 0).to_lowercase()))
+}
+
+/// `LENGTH(s)`：字符串的字节数
+fn length(args: &[Value]) -> Result<Value, ExecutionError> {
+    Ok(Value::Int(text_arg(args, 0).len() as i64))
+}
+
+/// `CHAR_LENGTH(s)`：字符串的字符数
+fn char_length(args: &[Value]) -> Result<Value, ExecutionError> {
+    Ok(Value::Int(text_arg(args, 0).chars().count() as i64))
+}
+
+/// `SUBSTRING(s, pos[, len])`：从第 `pos` 个字符起截取子串
+///
+/// `pos` 从 1 开始，为负数时从末尾倒数；`pos` 为 0 或 `len` 小于 1 时结果为空字符串。
+fn substring(args: &[Value]) -> Result<Value, ExecutionError> {
+    let s = text_arg(args, 0);
+    let pos = int_arg("SUBSTRING", args, 1)?;
+    let len = match args.get(2) {
+        Some(_) => int_arg("SUBSTRING", args, 2)?,
+        None => i64::MAX,
+    };
+    let char_count = s.chars().count() as i64;
+    let start = match pos {
+        pos if pos > 0 => pos - 1,
+        pos if pos < 0 && -pos <= char_count => char_count + pos,
+        _ => return Ok(Value::Varchar(String::new())),
+    };
+    if len < 1 {
+        return Ok(Value::Varchar(String::new()));
+    }
+    Ok(Value::Varchar(char_slice(
+        &s,
+        start as usize,
+        len.try_into().unwrap_or(usize::MAX),
+    )))
+}
+
+/// 去除字符串首尾重复出现的指定子串，对应 `TRIM`、`LTRIM` 和 `RTRIM`
+///
+/// # Arguments
+/// * `value` - 要处理的字符串
+/// * `remove` - 要去除的子串，为 None 时去除空格
+/// * `leading` - 是否去除开头的子串
+/// * `trailing` - 是否去除末尾的子串
+///
+/// # Returns
+/// * `Result<Value, ExecutionError>` - 处理后的字符串；字符串或子串为 NULL 时为 NULL
+pub fn trim(
+    value: &Value,
+    remove: Option<&Value>,
+    leading: bool,
+    trailing: bool,
+) -> Result<Value, ExecutionError> {
+    let Some(mut s) = value.to_text() else {
+        return Ok(Value::Null);
+    };
+    let remove = match remove {
+        Some(remove) => match remove.to_text() {
+            Some(remove) => remove,
+            None => return Ok(Value::Null),
+        },
+        None => " ".to_string(),
+    };
+    if remove.is_empty() {
+        return Ok(Value::Varchar(s));
+    }
+    if leading {
+        while let Some(rest) = s.strip_prefix(remove.as_str()) {
+            s = rest.to_string();
+        }
+    }
+    if trailing {
+        while let Some(rest) = s.strip_suffix(remove.as_str()) {
+            s = rest.to_string();
+        }
+    }
+    Ok(Value::Varchar(s))
+}
+
+/// `REPLACE(s, from, to)`：将所有 `from` 替换为 `to`，区分大小写
+fn replace(args: &[Value]) -> Result<Value, ExecutionError> {
+    let (s, from, to) = (text_arg(args, 0), text_arg(args, 1), text_arg(args, 2));
+    if from.is_empty() {
+        return Ok(Value::Varchar(s));
+    }
+    Ok(Value::Varchar(s.replace(&from, &to)))
+}
+
+/// `LPAD(s, len, pad)` 和 `RPAD(s, len, pad)`：用 `pad` 在左侧或右侧将字符串填充到 `len` 个字符
+///
+/// 字符串长于 `len` 时截断为前 `len` 个字符；`len` 为负数或超过结果的最大长度，
+/// 或者需要填充但 `pad` 为空时结果为 NULL。
+fn pad(name: &str, args: &[Value], left: bool) -> Result<Value, ExecutionError> {
+    let s = text_arg(args, 0);
+    let len = match usize::try_from(int_arg(name, args, 1)?) {
+        Ok(len) if len <= MAX_RESULT_LENGTH => len,
+        _ => return Ok(Value::Null),
+    };
+    let pad = text_arg(args, 2);
+    let char_count = s.chars().count();
+    if char_count >= len {
+        return Ok(Value::Varchar(char_slice(&s, 0, len)));
+    }
+    if pad.is_empty() {
+        return Ok(Value::Null);
+    }
+    let padding: String = pad.chars().cycle().take(len - char_count).collect();
+    Ok(Value::Varchar(if left {
+        padding + &s
+    } else {
+        s + &padding
+    }))
+}
+
+/// `LEFT(s, n)` 和 `RIGHT(s, n)`：取开头或末尾的 `n` 个字符，`n` 小于 1 时结果为空字符串
+fn take(name: &str, args: &[Value], from_left: bool) -> Result<Value, ExecutionError> {
+    let s = text_arg(args, 0);
+    let n = usize::try_from(int_arg(name, args, 1)?).unwrap_or(0);
+    let char_count = s.chars().count();
+    Ok(Value::Varchar(if from_left {
+        char_slice(&s, 0, n)
+    } else {
+        char_slice(&s, char_count.saturating_sub(n), n)
+    }))
+}
+
+/// `INSTR(s, sub)`：子串第一次出现的字符位置（从 1 开始），不存在时为 0
+fn instr(args: &[Value]) -> Result<Value, ExecutionError> {
+    let (s, sub) = (text_arg(args, 0), text_arg(args, 1));
+    Ok(Value::Int(s.find(&sub).map_or(0, |byte_index| {
+        s[..byte_index].chars().count() as i64 + 1
+    })))
+}
+
+/// `ABS(x)`：绝对值
+fn abs(args: &[Value]) -> Result<Value, ExecutionError> {
+    match numeric_arg("ABS", args, 0)? {
+        Value::Int(i) => i.checked_abs().map(Value::Int).ok_or_else(|| {
+            ExecutionError::ExecutionError(format!("整数运算溢出: ABS({}) 超出 BIGINT 的范围", i))
+        }),
+        Value::Float(f) => Ok(Value::Float(f.abs())),
+        _ => unreachable!("numeric_arg 只返回整数或浮点数"),
+    }
+}
+
+/// `ROUND(x[, d])`：四舍五入到小数点后 `d` 位，`d` 为负数时舍入到整数部分的对应位
+///
+/// 与 MySQL 一致，恰好位于中间的值向远离零的方向舍入，整数参数的结果仍为整数。
+fn round(args: &[Value]) -> Result<Value, ExecutionError> {
+    let value = numeric_arg("ROUND", args, 0)?;
+    let digits = match args.get(1) {
+        Some(_) => int_arg("ROUND", args, 1)?,
+        None => 0,
+    };
+    match value {
+        Value::Int(i) if digits >= 0 => Ok(Value::Int(i)),
+        Value::Int(i) => {
+            let Some(factor) = u32::try_from(-digits)
+                .ok()
+                .and_then(|exp| 10i64.checked_pow(exp))
+            else {
+                return Ok(Value::Int(0));
+            };
+            let remainder = i % factor;
+            let truncated = i - remainder;
+            if remainder.abs() * 2 < factor {
+                return Ok(Value::Int(truncated));
+            }
+            truncated
+                .checked_add(remainder.signum() * factor)
+                .map(Value::Int)
+                .ok_or_else(|| {
+                    ExecutionError::ExecutionError(format!(
+                        "整数运算溢出: ROUND({}, {}) 超出 BIGINT 的范围",
+                        i, digits
+                    ))
+                })
+        }
+        Value::Float(f) => {
+            let factor = 10f64.powi(digits.clamp(-308, 308) as i32);
+            Ok(Value::Float((f * factor).round() / factor))
+        }
+        _ => unreachable!("numeric_arg 只返回整数或浮点数"),
+    }
+}
+
+/// `FLOOR(x)` 和 `CEIL(x)`：向下或向上取整
+///
+/// 结果在整数范围内时返回整数，否则返回浮点数。
+fn round_to_integer(
+    name: &str,
+    args: &[Value],
+    rounding: fn(f64) -> f64,
+) -> Result<Value, ExecutionError> {
+    match numeric_arg(name, args, 0)? {
+        Value::Float(f) => {
+            let rounded = rounding(f);
+            if rounded >= i64::MIN as f64 && rounded < i64::MAX as f64 {
+                Ok(Value::Int(rounded as i64))
+            } else {
+                Ok(Value::Float(rounded))
+            }
+        }
+        value => Ok(value),
+    }
+}
+
+/// `POWER(x, y)`：`x` 的 `y` 次幂，结果为浮点数；结果不是有限的实数（如 `POWER(0, -1)`）时为 NULL
+fn power(args: &[Value]) -> Result<Value, ExecutionError> {
+    let result = float_arg("POWER", args, 0)?.powf(float_arg("POWER", args, 1)?);
+    Ok(if !result.is_finite() {
+        Value::Null
+    } else {
+        Value::Float(result)
+    })
+}
+
+/// `SQRT(x)`：平方根，`x` 为负数时为 NULL
+fn sqrt(args: &[Value]) -> Result<Value, ExecutionError> {
+    let x = float_arg("SQRT", args, 0)?;
+    Ok(if x < 0.0 {
+        Value::Null
+    } else {
+        Value::Float(x.sqrt())
+    })
+}
+
+/// `GREATEST(x, ...)` 和 `LEAST(x, ...)`：参数中的最大值或最小值，按比较运算符的规则比较
+fn extremum(args: &[Value], op: BinaryOperator) -> Result<Value, ExecutionError> {
+    let mut result = args[0].clone();
+    for arg in &args[1..] {
+        if ExprEvaluator::binary_op(&op, arg.clone(), result.clone())? == Value::Bool(true) {
+            result = arg.clone();
+        }
+    }
+    Ok(result)
+}
+
+/// `NULLIF(a, b)`：`a` 等于 `b` 时为 NULL，否则为 `a`
+fn nullif(args: &[Value]) -> Result<Value, ExecutionError> {
+    let equal = ExprEvaluator::binary_op(&BinaryOperator::Eq, args[0].clone(), args[1].clone())?;
+    Ok(if equal == Value::Bool(true) {
+        Value::Null
+    } else {
+        args[0].clone()
+    })
+}
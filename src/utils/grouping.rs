@@ -0,0 +1,381 @@
+/// 分组模块
+///
+/// 提供 `GROUP BY`（包括 `WITH ROLLUP`）的分组键解析、行分组，
+/// 以及分组查询中非聚合列引用的合法性检查。
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{
+    Expr, GroupByExpr, GroupByWithModifier, Query, SelectItem, Value as SqlValue, Visit, VisitMut,
+    Visitor, VisitorMut,
+};
+
+use crate::executor::table::Table;
+use crate::executor::ExecutionError;
+use crate::model::Value;
+use crate::utils::aggregate;
+use crate::utils::expr_evaluator::{EvalContext, ExprEvaluator};
+use crate::utils::query_processor::compare_sort_keys;
+
+/// 查询的分组方式
+#[derive(Debug, Clone, Default)]
+pub struct GroupBy {
+    /// 分组表达式，列别名和列序号已替换为对应的投影表达式
+    pub exprs: Vec<Expr>,
+    /// 是否带有 `WITH ROLLUP`
+    pub rollup: bool,
+}
+
+/// 一个分组
+#[derive(Debug, Clone)]
+pub struct RowGroup {
+    /// 分组包含的行索引
+    pub indices: Vec<usize>,
+    /// `WITH ROLLUP` 汇总行中被汇总掉的分组表达式个数（从最后一个分组表达式算起），普通分组为 0
+    pub rolled_up: usize,
+}
+
+impl GroupBy {
+    /// 从 SELECT 子句解析分组方式
+    ///
+    /// 与 MySQL 一致，`GROUP BY` 中的标识符优先解析为表的列，不是表列时再查找同名的列别名；
+    /// 整数常量表示 SELECT 列表中的列序号（从 1 开始）。
+    ///
+    /// # Arguments
+    /// * `group_by` - SELECT 子句中的 `GROUP BY`
+    /// * `column_projection` - 列投影列表
+    /// * `table` - 可选的数据源表
+    ///
+    /// # Returns
+    /// * `Result<GroupBy, ExecutionError>` - 解析后的分组方式或错误
+    pub fn new(
+        group_by: &GroupByExpr,
+        column_projection: &[SelectItem],
+        table: Option<&Table>,
+    ) -> Result<Self, ExecutionError> {
+        let GroupByExpr::Expressions(exprs, modifiers) = group_by else {
+            return Err(ExecutionError::ExecutionError(
+                "不支持 GROUP BY ALL".to_string(),
+            ));
+        };
+        let mut rollup = false;
+        for modifier in modifiers {
+            match modifier {
+                GroupByWithModifier::Rollup => rollup = true,
+                _ => {
+                    return Err(ExecutionError::ExecutionError(format!(
+                        "不支持的分组修饰符 {}",
+                        modifier
+                    )))
+                }
+            }
+        }
+        let exprs = exprs
+            .iter()
+            .map(|expr| Self::resolve_group_expr(expr, column_projection, table))
+            .collect::<Result<Vec<Expr>, ExecutionError>>()?;
+        if let Some(expr) = exprs
+            .iter()
+            .find(|expr| aggregate::contains_aggregate(expr))
+        {
+            return Err(ExecutionError::ExecutionError(format!(
+                "不能按聚合函数分组: {}",
+                expr
+            )));
+        }
+        Ok(Self { exprs, rollup })
+    }
+
+    /// 将过滤后的行按分组表达式的值分组
+    ///
+    /// 分组键通过哈希比较，NULL 与 NULL 属于同一分组；分组按首次出现的顺序排列。
+    /// 带 `WITH ROLLUP` 时分组按分组键升序排列，每组相同前缀的分组之后紧跟对应的汇总分组，
+    /// 最后是包含所有行的总计分组。
+    /// 没有分组表达式时（只有聚合函数的查询），所有行构成一个分组，即使没有任何行。
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文，其中的表为数据源
+    /// * `indices` - 参与分组的行索引
+    ///
+    /// # Returns
+    /// * `Result<Vec<RowGroup>, ExecutionError>` - 分组列表或错误
+    pub fn group_rows(
+        &self,
+        ctx: &EvalContext,
+        indices: &[usize],
+    ) -> Result<Vec<RowGroup>, ExecutionError> {
+        if self.exprs.is_empty() {
+            return Ok(vec![RowGroup {
+                indices: indices.to_vec(),
+                rolled_up: 0,
+            }]);
+        }
+
+        let mut positions: HashMap<Vec<Value>, usize> = HashMap::new();
+        let mut groups: Vec<(Vec<Value>, Vec<usize>)> = Vec::new();
+        for &idx in indices {
+            let row_ctx = match ctx.table {
+                Some(table) => ctx.with_row(&table.data[idx]),
+                None => *ctx,
+            };
+            let keys = self
+                .exprs
+                .iter()
+                .map(|expr| ExprEvaluator::evaluate(&row_ctx, expr))
+                .collect::<Result<Vec<Value>, ExecutionError>>()?;
+            match positions.get(&keys) {
+                Some(&pos) => groups[pos].1.push(idx),
+                None => {
+                    positions.insert(keys.clone(), groups.len());
+                    groups.push((keys, vec![idx]));
+                }
+            }
+        }
+
+        if !self.rollup {
+            return Ok(groups
+                .into_iter()
+                .map(|(_, indices)| RowGroup {
+                    indices,
+                    rolled_up: 0,
+                })
+                .collect());
+        }
+
+        let asc = vec![true; self.exprs.len()];
+        groups.sort_by(|(keys1, _), (keys2, _)| compare_sort_keys(keys1, keys2, &asc));
+
+        // pending[len] 收集分组键前缀长度为 len 的汇总分组中的行，前缀变化时输出
+        let level_count = self.exprs.len();
+        let mut pending: Vec<Vec<usize>> = vec![Vec::new(); level_count];
+        let mut result = Vec::with_capacity(groups.len() * 2);
+        for (pos, (keys, indices)) in groups.iter().enumerate() {
+            for rows in pending.iter_mut() {
+                rows.extend(indices);
+            }
+            result.push(RowGroup {
+                indices: indices.clone(),
+                rolled_up: 0,
+            });
+            // 与下一分组共同前缀之外的汇总分组到此结束；最后一个分组之后输出全部汇总分组
+            let first_closed = match groups.get(pos + 1) {
+                Some((next_keys, _)) => {
+                    keys.iter()
+                        .zip(next_keys)
+                        .take_while(|(key, next)| key == next)
+                        .count()
+                        + 1
+                }
+                None => 0,
+            };
+            for len in (first_closed..level_count).rev() {
+                result.push(RowGroup {
+                    indices: std::mem::take(&mut pending[len]),
+                    rolled_up: level_count - len,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// 检查分组查询中的表达式只引用了分组表达式或聚合函数
+    ///
+    /// 与 MySQL 的 `ONLY_FULL_GROUP_BY` 模式一致，聚合函数之外的列引用
+    /// 必须出现在 `GROUP BY` 中（或属于某个分组表达式）。相关子查询中对外层查询列的引用
+    /// 在每次执行子查询时都是常量，不受此限制。
+    ///
+    /// # Arguments
+    /// * `ctx` - 查询的求值上下文，用于识别对外层查询列的引用
+    /// * `expr` - 要检查的表达式（投影、`HAVING` 或 `ORDER BY` 中的表达式）
+    ///
+    /// # Returns
+    /// * `Result<(), ExecutionError>` - 检查通过返回 Ok，否则返回错误
+    pub fn validate_expr(&self, ctx: &EvalContext, expr: &Expr) -> Result<(), ExecutionError> {
+        let mut finder = UngroupedColumnFinder {
+            ctx,
+            group_exprs: &self.exprs,
+            query_depth: 0,
+            skipped: None,
+        };
+        match Visit::visit(expr, &mut finder) {
+            ControlFlow::Break(column) => Err(ExecutionError::ExecutionError(format!(
+                "列 '{}' 不在 GROUP BY 子句中，也不在聚合函数中",
+                column
+            ))),
+            ControlFlow::Continue(()) => Ok(()),
+        }
+    }
+
+    /// 将 `GROUP BY` 中的列别名和列序号替换为对应的投影表达式
+    fn resolve_group_expr(
+        expr: &Expr,
+        column_projection: &[SelectItem],
+        table: Option<&Table>,
+    ) -> Result<Expr, ExecutionError> {
+        match expr {
+            Expr::Value(value) => {
+                let SqlValue::Number(n, _) = &value.value else {
+                    return Ok(expr.clone());
+                };
+                let position = n.parse::<usize>().unwrap_or(0);
+                match position
+                    .checked_sub(1)
+                    .and_then(|idx| column_projection.get(idx))
+                {
+                    Some(SelectItem::UnnamedExpr(expr))
+                    | Some(SelectItem::ExprWithAlias { expr, .. }) => Ok(expr.clone()),
+                    _ => Err(ExecutionError::ExecutionError(format!(
+                        "GROUP BY 中的列序号 {} 无效",
+                        n
+                    ))),
+                }
+            }
+            Expr::Identifier(ident)
+                if ident.quote_style.is_none()
+                    && table.is_none_or(|table| table.get_column_index(&ident.value).is_none()) =>
+            {
+                Ok(find_aliased_expr(&ident.value, column_projection)
+                    .cloned()
+                    .unwrap_or_else(|| expr.clone()))
+            }
+            _ => Ok(expr.clone()),
+        }
+    }
+}
+
+impl RowGroup {
+    /// 返回求值该分组的投影、`HAVING` 和排序表达式时使用的上下文
+    ///
+    /// # Arguments
+    /// * `ctx` - 查询的求值上下文
+    /// * `group_by` - 查询的分组方式
+    pub fn context<'a>(&'a self, ctx: &EvalContext<'a>, group_by: &'a GroupBy) -> EvalContext<'a> {
+        let rolled_up = &group_by.exprs[group_by.exprs.len() - self.rolled_up..];
+        ctx.with_group(&self.indices).with_rolled_up(rolled_up)
+    }
+}
+
+/// 将表达式中引用的列别名替换为对应的投影表达式
+///
+/// 用于 `HAVING` 子句，使 `HAVING total > 10` 可以引用 `SUM(amount) AS total`。
+/// 与 `ORDER BY` 一致，别名优先于同名的表列；子查询中的标识符不做替换。
+///
+/// # Arguments
+/// * `expr` - 要替换的表达式
+/// * `column_projection` - 列投影列表
+///
+/// # Returns
+/// * `Expr` - 替换别名后的表达式
+pub fn resolve_aliases(expr: &Expr, column_projection: &[SelectItem]) -> Expr {
+    let mut expr = expr.clone();
+    let mut resolver = AliasResolver {
+        column_projection,
+        query_depth: 0,
+    };
+    let _ = VisitMut::visit(&mut expr, &mut resolver);
+    expr
+}
+
+/// 在列投影中查找别名对应的表达式
+fn find_aliased_expr<'a>(name: &str, column_projection: &'a [SelectItem]) -> Option<&'a Expr> {
+    column_projection.iter().find_map(|item| match item {
+        SelectItem::ExprWithAlias { expr, alias } if alias.value == name => Some(expr),
+        _ => None,
+    })
+}
+
+/// 查找不在分组表达式和聚合函数中的列引用的访问器
+struct UngroupedColumnFinder<'a> {
+    /// 查询的求值上下文
+    ctx: &'a EvalContext<'a>,
+    /// 分组表达式
+    group_exprs: &'a [Expr],
+    /// 当前所在子查询的嵌套深度
+    query_depth: usize,
+    /// 正在跳过的子表达式（分组表达式或聚合函数调用）
+    skipped: Option<*const Expr>,
+}
+
+impl Visitor for UngroupedColumnFinder<'_> {
+    type Break = String;
+
+    fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        self.query_depth += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        self.query_depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        if self.skipped.is_some() || self.query_depth > 0 {
+            return ControlFlow::Continue(());
+        }
+        let is_aggregate =
+            matches!(expr, Expr::Function(function) if aggregate::is_aggregate_call(function));
+        if is_aggregate || self.group_exprs.contains(expr) {
+            self.skipped = Some(expr as *const Expr);
+            return ControlFlow::Continue(());
+        }
+        let (qualifier, column) = match expr {
+            Expr::Identifier(ident) if ident.quote_style.is_none() => (None, ident),
+            Expr::CompoundIdentifier(idents) => match idents.as_slice() {
+                [qualifier, column] => (Some(qualifier.value.as_str()), column),
+                _ => return ControlFlow::Break(expr.to_string()),
+            },
+            _ => return ControlFlow::Continue(()),
+        };
+        if ExprEvaluator::is_outer_column(self.ctx, qualifier, &column.value) {
+            return ControlFlow::Continue(());
+        }
+        ControlFlow::Break(match qualifier {
+            Some(_) => expr.to_string(),
+            None => column.value.clone(),
+        })
+    }
+
+    fn post_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        if self.skipped == Some(expr as *const Expr) {
+            self.skipped = None;
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// 替换列别名的访问器
+struct AliasResolver<'a> {
+    /// 列投影列表
+    column_projection: &'a [SelectItem],
+    /// 当前所在子查询的嵌套深度
+    query_depth: usize,
+}
+
+impl VisitorMut for AliasResolver<'_> {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, _query: &mut Query) -> ControlFlow<Self::Break> {
+        self.query_depth += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &mut Query) -> ControlFlow<Self::Break> {
+        self.query_depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<Self::Break> {
+        if self.query_depth > 0 {
+            return ControlFlow::Continue(());
+        }
+        if let Expr::Identifier(ident) = expr {
+            if ident.quote_style.is_none() {
+                if let Some(aliased) = find_aliased_expr(&ident.value, self.column_projection) {
+                    *expr = aliased.clone();
+                }
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
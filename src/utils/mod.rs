@@ -1,8 +1,14 @@
 /// 工具函数模块
 ///
 /// 包含系统中使用的通用工具函数和子模块，如表达式求值、日志记录等。
+pub mod aggregate;
+pub mod coercion;
 pub mod expr_evaluator;
+pub mod function;
+pub mod grouping;
+pub mod pattern;
 pub mod query_processor;
+pub mod window;
 
 use colored::Colorize;
 use lazy_static::lazy_static;
@@ -11,6 +17,8 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 lazy_static! {
     pub static ref IS_INFO_OUTPUT: AtomicBool = AtomicBool::new(true);
+    /// 是否启用严格模式，启用时写入列的值在隐式类型转换中不允许丢失信息，默认启用
+    pub static ref IS_STRICT_MODE: AtomicBool = AtomicBool::new(true);
 }
 
 /// 输出错误日志
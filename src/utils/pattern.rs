@@ -0,0 +1,113 @@
+/// 模式匹配模块
+///
+/// 实现 `LIKE`、`ILIKE` 和 MySQL 的 `REGEXP`/`RLIKE`。`LIKE` 模式被转换为等价的正则表达式，
+/// 所有正则表达式都通过 `regex` crate 编译，并在一条语句的执行过程中缓存，
+/// 避免对每一行重复编译相同的模式。
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::executor::ExecutionError;
+use crate::model::Value;
+
+/// `LIKE` 的默认转义字符，与 MySQL 一致
+const DEFAULT_ESCAPE: char = '\\';
+
+lazy_static! {
+    /// 当前语句中已编译的正则表达式，以正则表达式的源码为键
+    static ref REGEX_CACHE: Mutex<HashMap<String, Regex>> = Mutex::new(HashMap::new());
+}
+
+/// 清空正则表达式缓存
+///
+/// 每条语句执行前调用，缓存只在一条语句的执行过程中有效。
+pub fn clear_cache() {
+    REGEX_CACHE.lock().unwrap().clear();
+}
+
+/// 判断值是否匹配 `LIKE` 模式
+///
+/// `%` 匹配任意个字符，`_` 匹配一个字符，转义字符之后的字符按字面匹配。
+/// 模式必须匹配整个值。
+///
+/// # Arguments
+/// * `value` - 被匹配的值
+/// * `pattern` - `LIKE` 模式
+/// * `escape` - `ESCAPE` 子句指定的转义字符，未指定时为 `\`
+/// * `case_insensitive` - 是否忽略大小写，对应 `ILIKE`
+///
+/// # Returns
+/// * `Result<Value, ExecutionError>` - 匹配结果；值或模式为 NULL 时为 NULL
+pub fn like(
+    value: &Value,
+    pattern: &Value,
+    escape: Option<&str>,
+    case_insensitive: bool,
+) -> Result<Value, ExecutionError> {
+    let escape = match escape {
+        None => DEFAULT_ESCAPE,
+        Some(escape) => {
+            let mut chars = escape.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => {
+                    return Err(ExecutionError::ExecutionError(format!(
+                        "ESCAPE 必须是单个字符，实际为 '{}'",
+                        escape
+                    )))
+                }
+            }
+        }
+    };
+    let (Some(value), Some(pattern)) = (value.to_text(), pattern.to_text()) else {
+        return Ok(Value::Null);
+    };
+
+    let mut source = String::from(if case_insensitive { "(?is)^" } else { "(?s)^" });
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // 模式末尾的转义字符按字面匹配
+            c if c == escape => {
+                let literal = chars.next().unwrap_or(escape);
+                source.push_str(&regex::escape(&literal.to_string()));
+            }
+            '%' => source.push_str(".*"),
+            '_' => source.push('.'),
+            c => source.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    source.push('$');
+    is_match(&source, &value).map(Value::Bool)
+}
+
+/// 判断值是否包含匹配正则表达式的子串，对应 MySQL 的 `REGEXP`/`RLIKE`
+///
+/// # Arguments
+/// * `value` - 被匹配的值
+/// * `pattern` - 正则表达式
+///
+/// # Returns
+/// * `Result<Value, ExecutionError>` - 匹配结果；值或模式为 NULL 时为 NULL，正则表达式无效时返回错误
+pub fn regexp(value: &Value, pattern: &Value) -> Result<Value, ExecutionError> {
+    let (Some(value), Some(pattern)) = (value.to_text(), pattern.to_text()) else {
+        return Ok(Value::Null);
+    };
+    is_match(&pattern, &value).map(Value::Bool)
+}
+
+/// 使用缓存的正则表达式匹配字符串
+fn is_match(source: &str, text: &str) -> Result<bool, ExecutionError> {
+    let mut cache = REGEX_CACHE.lock().unwrap();
+    if let Some(regex) = cache.get(source) {
+        return Ok(regex.is_match(text));
+    }
+    let regex = Regex::new(source).map_err(|err| {
+        ExecutionError::ExecutionError(format!("无效的正则表达式 '{}': {}", source, err))
+    })?;
+    let matched = regex.is_match(text);
+    cache.insert(source.to_string(), regex);
+    Ok(matched)
+}
@@ -1,30 +1,163 @@
+use crate::executor::join::Scope;
 use crate::executor::table::Table;
 use crate::executor::ExecutionError;
 use crate::executor::EXECUTOR_INPUT;
 use crate::model::Value;
-use crate::utils::expr_evaluator::ExprEvaluator;
+use crate::utils::aggregate;
+use crate::utils::expr_evaluator::{EvalContext, ExprEvaluator};
+use crate::utils::grouping::{GroupBy, RowGroup};
 /// 查询处理器模块
 ///
 /// 提供查询处理功能，包括处理查询投影、过滤和排序等操作。
-use sqlparser::ast::{OrderBy, OrderByKind, SelectItem, Spanned};
+use sqlparser::ast::{
+    Expr, Ident, OrderBy, OrderByKind, Query, SelectItem, SelectItemQualifiedWildcardKind, Spanned,
+    Value as SqlValue,
+};
 use sqlparser::tokenizer::Location;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
 
 fn extract_original_str(s: &str, start: Location, end: Location) -> Option<String> {
     let lines: Vec<&str> = s.lines().collect();
 
-    let start_line = (start.line - 1) as usize;
-    let end_line = (end.line - 1) as usize;
+    // `REGEXP`/`RLIKE` 等表达式没有位置信息，行号为 0
+    let start_line = start.line.checked_sub(1)? as usize;
+    let end_line = end.line.checked_sub(1)? as usize;
 
-    if start_line!=end_line{
+    if start_line != end_line {
         return None;
     }
 
-    let ret = lines[start_line]
-        .chars()
-        .skip(start.column as usize - 1)
-        .take((end.column- start.column) as usize)
-        .collect::<String>();
-    Some(ret)
+    let chars: Vec<char> = lines.get(start_line)?.chars().collect();
+    let mut begin = (start.column as usize).checked_sub(1)?;
+    let mut stop = ((end.column as usize).saturating_sub(1)).min(chars.len());
+    if begin > stop {
+        return None;
+    }
+
+    // 一元运算和括号表达式的 span 不包含前缀的操作符和左括号，
+    // 子查询的 span 也不包含左括号，这里向前补全
+    while let Some(prev) = (0..begin).rev().find(|&i| !chars[i].is_whitespace()) {
+        match chars[prev] {
+            '-' | '+' | '~' | '!' | '(' => begin = prev,
+            _ if prev >= 2
+                && chars[prev - 2..=prev]
+                    .iter()
+                    .collect::<String>()
+                    .eq_ignore_ascii_case("NOT")
+                && (prev == 2 || !chars[prev - 3].is_alphanumeric()) =>
+            {
+                begin = prev - 2
+            }
+            _ => break,
+        }
+    }
+
+    // 函数调用的 span 不包含右括号，`COUNT(*)` 这类调用甚至不包含参数列表，
+    // 窗口函数的 span 也不包含 `OVER (...)` 子句，这里补全
+    loop {
+        let depth = chars[begin..stop].iter().fold(0i32, |depth, c| match c {
+            '(' => depth + 1,
+            ')' => depth - 1,
+            _ => depth,
+        });
+        let next = (stop..chars.len()).find(|&i| !chars[i].is_whitespace());
+        match next.map(|i| (i, chars[i])) {
+            Some((i, ')')) if depth > 0 => stop = i + 1,
+            Some((i, '('))
+                if depth == 0
+                    && stop > begin
+                    && (chars[stop - 1].is_alphanumeric() || chars[stop - 1] == '_') =>
+            {
+                stop = matching_paren(&chars, i)? + 1;
+            }
+            Some((i, _)) if depth == 0 && stop > begin && chars[stop - 1] == ')' => {
+                let keyword: String = chars[i..].iter().take(4).collect();
+                let open = (i + 4..chars.len()).find(|&j| !chars[j].is_whitespace());
+                match open {
+                    Some(open) if keyword.eq_ignore_ascii_case("OVER") && chars[open] == '(' => {
+                        stop = matching_paren(&chars, open)? + 1;
+                    }
+                    _ => break,
+                }
+            }
+            _ => break,
+        }
+    }
+
+    Some(chars[begin..stop].iter().collect())
+}
+
+/// 返回与 `open` 处的左括号匹配的右括号位置
+fn matching_paren(chars: &[char], open: usize) -> Option<usize> {
+    let mut level = 0;
+    (open..chars.len()).find(|&j| {
+        match chars[j] {
+            '(' => level += 1,
+            ')' => level -= 1,
+            _ => {}
+        }
+        level == 0
+    })
+}
+
+/// 结果行数限制
+///
+/// 对应 `LIMIT n OFFSET m` 以及 MySQL 的 `LIMIT m, n`，在排序之后应用。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RowLimit {
+    /// 跳过的行数
+    pub offset: usize,
+    /// 最多返回的行数，None 表示不限制
+    pub limit: Option<usize>,
+}
+
+impl RowLimit {
+    /// 从查询语句中解析行数限制
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - 查询语句
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RowLimit, ExecutionError>` - 行数限制，`LIMIT`/`OFFSET` 的值必须是非负整数
+    pub fn from_query(query: &Query) -> Result<Self, ExecutionError> {
+        let evaluate_count = |expr: &Expr, clause: &str| match ExprEvaluator::evaluate(
+            &EvalContext::default(),
+            expr,
+        )? {
+            Value::Int(n) if n >= 0 => Ok(n as usize),
+            value => Err(ExecutionError::ExecutionError(format!(
+                "{} 的值必须是非负整数，实际为 {}",
+                clause, value
+            ))),
+        };
+        Ok(Self {
+            offset: match &query.offset {
+                Some(offset) => evaluate_count(&offset.value, "OFFSET")?,
+                None => 0,
+            },
+            limit: match &query.limit {
+                Some(limit) => Some(evaluate_count(limit, "LIMIT")?),
+                None => None,
+            },
+        })
+    }
+
+    /// 排序时需要保留的行数，即 `offset + limit`
+    pub fn top_k(&self) -> Option<usize> {
+        self.limit.map(|limit| self.offset.saturating_add(limit))
+    }
+
+    /// 对排序后的结果应用 `OFFSET` 和 `LIMIT`
+    pub fn apply<T>(&self, items: Vec<T>) -> Vec<T> {
+        items
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
 }
 
 /// 查询处理器
@@ -35,30 +168,27 @@ pub struct QueryProcessor;
 impl QueryProcessor {
     /// 提取行数据
     ///
-    /// 根据过滤条件和列投影提取行数据
+    /// 按给定的行索引顺序对每一行进行列投影
     ///
     /// # Arguments
     ///
-    /// * `table` - 表对象
-    /// * `sorted_indices` - 排序后的行索引
-    /// * `filter_indices` - 过滤后的行索引
+    /// * `ctx` - 求值上下文，其中的表为查询的数据源
+    /// * `indices` - 已过滤、排序并截取后的行索引
     /// * `column_projection` - 列投影列表
     ///
     /// # Returns
     ///
     /// * `Result<Vec<Vec<Value>>, ExecutionError>` - 结果行数据或错误
     pub fn extract_rows(
-        table: &Table,
-        sorted_indices: &[usize],
-        filter_indices: &[usize],
+        ctx: &EvalContext,
+        indices: &[usize],
         column_projection: &[SelectItem],
     ) -> Result<Vec<Vec<Value>>, ExecutionError> {
-        sorted_indices
+        Self::source_table(ctx)?;
+        indices
             .iter()
-            .filter(|&&idx| filter_indices.contains(&idx))
             .map(|&idx| {
-                let row = &table.data[idx];
-                let values = Self::process_projection(Some(table), Some(row), column_projection)?;
+                let values = Self::process_projection(&ctx.with_row_at(idx), column_projection)?;
                 Ok(values)
             })
             .collect()
@@ -70,29 +200,31 @@ impl QueryProcessor {
     ///
     /// # Arguments
     ///
-    /// * `table` - 可选的表对象
-    /// * `row` - 可选的行数据
+    /// * `ctx` - 求值上下文，包含可选的表对象和行数据
     /// * `column_projection` - 列投影列表
     ///
     /// # Returns
     ///
     /// * `Result<Vec<Value>, ExecutionError>` - 处理后的行数据或错误
     pub fn process_projection(
-        table: Option<&Table>,
-        row: Option<&[Value]>,
+        ctx: &EvalContext,
         column_projection: &[SelectItem],
     ) -> Result<Vec<Value>, ExecutionError> {
         let values = column_projection
             .iter()
             .map(|item| match item {
-                SelectItem::UnnamedExpr(expr) => {
-                    ExprEvaluator::evaluate_expr(table, expr, row).map(|val| vec![val])
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    ExprEvaluator::evaluate(ctx, expr).map(|val| vec![val])
+                }
+                SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => {
+                    let row = ctx.row.ok_or(ExecutionError::ExecutionError(
+                        "无法在无表环境下使用通配符".to_string(),
+                    ))?;
+                    Ok(Self::wildcard_columns(ctx, item)?
+                        .into_iter()
+                        .map(|(_, indices)| Scope::value(row, &indices))
+                        .collect())
                 }
-                SelectItem::Wildcard(_) => Ok(row.unwrap().to_vec()),
-                _ => Err(ExecutionError::ExecutionError(format!(
-                    "不支持的列投影类型: {}",
-                    item
-                ))),
             })
             .collect::<Result<Vec<Vec<Value>>, ExecutionError>>()?
             .into_iter()
@@ -103,113 +235,446 @@ impl QueryProcessor {
 
     /// 提取列名
     ///
-    /// 从查询的选择项中提取列名
+    /// 从查询的选择项中提取列名。带别名的表达式使用别名作为列名，
+    /// 限定列名使用其列名部分，其余表达式使用其在原始 SQL 中的文本。
     ///
     /// # Arguments
     ///
-    /// * `table` - 可选的表对象
+    /// * `ctx` - 求值上下文，包含可选的表对象
     /// * `column_projection` - 列投影列表
     ///
     /// # Returns
     ///
     /// * `Result<Vec<String>, ExecutionError>` - 列名列表或错误
     pub fn extract_columns_name(
-        table: Option<&Table>,
+        ctx: &EvalContext,
         column_projection: &[SelectItem],
     ) -> Result<Vec<String>, ExecutionError> {
         Ok(column_projection
             .iter()
-            .flat_map(|item| match item {
-                SelectItem::UnnamedExpr(expr) => vec![extract_original_str(
+            .map(|item| match item {
+                // 与 MySQL 一致，限定列名 `t.col` 的结果列名为 `col`
+                SelectItem::UnnamedExpr(Expr::CompoundIdentifier(idents)) => Ok(vec![idents
+                    .last()
+                    .map_or(String::new(), |ident| ident.value.clone())]),
+                // CASE、SUBSTRING、TRIM 等特殊语法的表达式的 span 不包含关键字，使用其规范化的 SQL 文本
+                SelectItem::UnnamedExpr(
+                    expr @ (Expr::Case { .. }
+                    | Expr::Substring { .. }
+                    | Expr::Trim { .. }
+                    | Expr::Ceil { .. }
+                    | Expr::Floor { .. }
+                    | Expr::Cast { .. }
+                    | Expr::Convert { .. }),
+                ) => Ok(vec![expr.to_string()]),
+                SelectItem::UnnamedExpr(expr) => Ok(vec![extract_original_str(
                     &EXECUTOR_INPUT.lock().unwrap().to_string(),
                     expr.span().start,
                     expr.span().end,
                 )
-                .unwrap()],
-                SelectItem::Wildcard(_) => table
-                    .unwrap()
-                    .columns
-                    .iter()
-                    .map(|col| col.name.clone())
-                    .collect(),
-                _ => vec![],
+                .unwrap_or_else(|| expr.to_string())]),
+                SelectItem::ExprWithAlias { alias, .. } => Ok(vec![alias.value.clone()]),
+                SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => {
+                    Ok(Self::wildcard_columns(ctx, item)?
+                        .into_iter()
+                        .map(|(name, _)| name)
+                        .collect())
+                }
             })
+            .collect::<Result<Vec<Vec<String>>, ExecutionError>>()?
+            .into_iter()
+            .flatten()
             .collect())
     }
 
+    /// 判断查询是否为聚合查询
+    ///
+    /// # Arguments
+    ///
+    /// * `column_projection` - 列投影列表
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - 任一投影表达式中包含聚合函数时返回 true
+    pub fn is_aggregate_query(column_projection: &[SelectItem]) -> bool {
+        column_projection.iter().any(|item| match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                aggregate::contains_aggregate(expr)
+            }
+            _ => false,
+        })
+    }
+
+    /// 返回排序条件中的所有排序表达式
+    ///
+    /// # Arguments
+    ///
+    /// * `order_by_clause` - 可选的排序条件
+    pub fn order_by_exprs(order_by_clause: &Option<OrderBy>) -> Vec<&Expr> {
+        match order_by_clause.as_ref().map(|order_by| &order_by.kind) {
+            Some(OrderByKind::Expressions(exprs)) => {
+                exprs.iter().map(|order_expr| &order_expr.expr).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// 对结果行去重
+    ///
+    /// 通过对整行的值进行哈希实现去重，保留每组重复行中第一次出现的行及其顺序。
+    /// NULL 与 NULL 视为相同的值。
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - 投影后的结果行
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Value>>` - 去重后的结果行
+    pub fn deduplicate_rows(rows: Vec<Vec<Value>>) -> Vec<Vec<Value>> {
+        let mut seen = HashSet::with_capacity(rows.len());
+        rows.into_iter()
+            .filter(|row| seen.insert(row.clone()))
+            .collect()
+    }
+
+    /// 将 ORDER BY 中引用的列别名和列位置替换为对应的表达式
+    ///
+    /// 排序在投影之前基于原始行进行，因此 `ORDER BY total` 这类引用
+    /// `SELECT price * qty AS total` 中别名的排序项需要先还原为 `price * qty`。
+    /// 与 MySQL 一致，别名优先于同名的表列；整数常量（如 `ORDER BY 2`）表示按结果中的第几列排序。
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - 求值上下文，用于计算通配符展开后的列数
+    /// * `order_by_clause` - 可选的排序条件
+    /// * `column_projection` - 列投影列表
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<OrderBy>, ExecutionError>` - 替换后的排序条件，列位置超出范围
+    ///   或指向多表连接中通配符展开的列时返回错误
+    pub fn resolve_order_by_aliases(
+        ctx: &EvalContext,
+        order_by_clause: &Option<OrderBy>,
+        column_projection: &[SelectItem],
+    ) -> Result<Option<OrderBy>, ExecutionError> {
+        let Some(mut order_by) = order_by_clause.clone() else {
+            return Ok(None);
+        };
+        if let OrderByKind::Expressions(exprs) = &mut order_by.kind {
+            for order_expr in exprs.iter_mut() {
+                match &order_expr.expr {
+                    Expr::Identifier(ident) => {
+                        let aliased_expr = column_projection.iter().find_map(|item| match item {
+                            SelectItem::ExprWithAlias { expr, alias }
+                                if alias.value == ident.value =>
+                            {
+                                Some(expr)
+                            }
+                            _ => None,
+                        });
+                        if let Some(expr) = aliased_expr {
+                            order_expr.expr = expr.clone();
+                        }
+                    }
+                    Expr::Value(value) => {
+                        if let SqlValue::Number(n, _) = &value.value {
+                            order_expr.expr = Self::positional_expr(ctx, n, column_projection)?;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(Some(order_by))
+    }
+
+    /// 返回 ORDER BY 中列位置对应的投影表达式
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - 求值上下文，用于计算通配符展开后的列数
+    /// * `position` - 列位置的文本，从 1 开始
+    /// * `column_projection` - 列投影列表
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Expr, ExecutionError>` - 对应的投影表达式，位置无效或指向多表连接中通配符展开的列时返回错误
+    fn positional_expr(
+        ctx: &EvalContext,
+        position: &str,
+        column_projection: &[SelectItem],
+    ) -> Result<Expr, ExecutionError> {
+        let out_of_range =
+            || ExecutionError::ExecutionError(format!("ORDER BY 中的列位置 {} 超出范围", position));
+        let mut remaining = position
+            .parse::<usize>()
+            .ok()
+            .and_then(|pos| pos.checked_sub(1))
+            .ok_or_else(out_of_range)?;
+        for item in column_projection {
+            let width = match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    if remaining == 0 {
+                        return Ok(expr.clone());
+                    }
+                    1
+                }
+                SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => {
+                    let columns = Self::wildcard_columns(ctx, item)?;
+                    match columns.get(remaining) {
+                        // 单表查询中通配符展开的列可以直接按列名引用
+                        Some((name, _)) if ctx.scope.is_none() => {
+                            return Ok(Expr::Identifier(Ident::new(name)));
+                        }
+                        Some(_) => {
+                            return Err(ExecutionError::ExecutionError(format!(
+                                "暂不支持在多表连接中按通配符展开的列位置 {} 排序",
+                                position
+                            )))
+                        }
+                        None => columns.len(),
+                    }
+                }
+            };
+            remaining -= width;
+        }
+        Err(out_of_range())
+    }
+
     /// 按排序条件排序行
     ///
+    /// 每行的排序键只计算一次。指定 `top_k` 时使用容量为 k 的有界堆，
+    /// 只保留排序后的前 k 行，避免为了少量结果对整张表排序。
+    /// 排序是稳定的：排序键相同的行保持原有的相对顺序。
+    ///
     /// # Arguments
     ///
-    /// * `table` - 表对象
+    /// * `ctx` - 求值上下文，其中的表为要排序的数据源
+    /// * `indices` - 参与排序的行索引（通常为过滤后的行）
     /// * `order_by` - 可选的排序条件
+    /// * `top_k` - 可选的保留行数，为 None 时返回全部行
     ///
     /// # Returns
     ///
     /// * `Result<Vec<usize>, ExecutionError>` - 排序后的行索引或错误
     pub fn sort_rows_by_order(
-        table: &Table,
+        ctx: &EvalContext,
+        indices: &[usize],
         order_by_clause: &Option<OrderBy>,
+        top_k: Option<usize>,
     ) -> Result<Vec<usize>, ExecutionError> {
-        match order_by_clause {
-            Some(order_by) => {
-                let order_by_expr = match &order_by.kind {
-                    OrderByKind::Expressions(exprs) => exprs,
-                    OrderByKind::All(_) => {
-                        return Err(ExecutionError::ExecutionError(
-                            "不支持的排序类型".to_string(),
-                        ));
-                    }
-                };
-
-                // 创建索引数组
-                let mut indices: Vec<usize> = (0..table.data.len()).collect();
-
-                // 根据排序条件对索引进行排序
-                indices.sort_by(|&i, &j| {
-                    let row1 = &table.data[i];
-                    let row2 = &table.data[j];
-
-                    for order_expr in order_by_expr {
-                        let val1 = match ExprEvaluator::evaluate_expr(
-                            Some(table),
-                            &order_expr.expr,
-                            Some(row1),
-                        ) {
-                            Ok(val) => val,
-                            Err(_) => return std::cmp::Ordering::Equal,
-                        };
-                        let val2 = match ExprEvaluator::evaluate_expr(
-                            Some(table),
-                            &order_expr.expr,
-                            Some(row2),
-                        ) {
-                            Ok(val) => val,
-                            Err(_) => return std::cmp::Ordering::Equal,
-                        };
-
-                        let comparison =
-                            val1.partial_cmp(&val2).unwrap_or(std::cmp::Ordering::Equal);
-
-                        let ordered = if order_expr.options.asc.unwrap_or(true) {
-                            comparison // ASC排序 默认
-                        } else {
-                            comparison.reverse() // DESC排序
-                        };
-
-                        if ordered != std::cmp::Ordering::Equal {
-                            return ordered;
-                        }
-                    }
-                    std::cmp::Ordering::Equal
-                });
+        Self::source_table(ctx)?;
+        Self::sort_by_order(indices, order_by_clause, top_k, |idx, expr| {
+            ExprEvaluator::evaluate(&ctx.with_row_at(idx), expr)
+        })
+    }
 
-                Ok(indices)
+    /// 按排序条件排序分组
+    ///
+    /// 排序表达式在分组上下文中求值，因此可以包含聚合函数。
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - 求值上下文，其中的表为分组的数据源
+    /// * `groups` - 所有分组
+    /// * `group_by` - 查询的分组方式
+    /// * `positions` - 参与排序的分组位置（通常为满足 `HAVING` 的分组）
+    /// * `order_by` - 可选的排序条件
+    /// * `top_k` - 可选的保留分组数，为 None 时返回全部分组
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<usize>, ExecutionError>` - 排序后的分组位置或错误
+    pub fn sort_groups_by_order(
+        ctx: &EvalContext,
+        groups: &[RowGroup],
+        group_by: &GroupBy,
+        positions: &[usize],
+        order_by_clause: &Option<OrderBy>,
+        top_k: Option<usize>,
+    ) -> Result<Vec<usize>, ExecutionError> {
+        Self::sort_by_order(positions, order_by_clause, top_k, |pos, expr| {
+            ExprEvaluator::evaluate(&groups[pos].context(ctx, group_by), expr)
+        })
+    }
+
+    /// 按排序条件排序一组位置
+    ///
+    /// 每个位置的排序键只计算一次。指定 `top_k` 时使用容量为 k 的有界堆，
+    /// 只保留排序后的前 k 个位置。排序是稳定的。
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - 参与排序的位置
+    /// * `order_by` - 可选的排序条件
+    /// * `top_k` - 可选的保留个数
+    /// * `evaluate` - 在给定位置上求值排序表达式
+    fn sort_by_order<F>(
+        indices: &[usize],
+        order_by_clause: &Option<OrderBy>,
+        top_k: Option<usize>,
+        evaluate: F,
+    ) -> Result<Vec<usize>, ExecutionError>
+    where
+        F: Fn(usize, &Expr) -> Result<Value, ExecutionError>,
+    {
+        let order_by_expr = match order_by_clause.as_ref().map(|order_by| &order_by.kind) {
+            Some(OrderByKind::Expressions(exprs)) => exprs,
+            Some(OrderByKind::All(_)) => {
+                return Err(ExecutionError::ExecutionError(
+                    "不支持的排序类型".to_string(),
+                ));
             }
             None => {
-                // 没有排序条件时，返回原始索引
-                Ok((0..table.data.len()).collect())
+                // 没有排序条件时，保持原始顺序
+                let k = top_k.unwrap_or(indices.len());
+                return Ok(indices.iter().take(k).copied().collect());
+            }
+        };
+        let asc: Vec<bool> = order_by_expr
+            .iter()
+            .map(|order_expr| order_expr.options.asc.unwrap_or(true)) // ASC排序 默认
+            .collect();
+
+        let sort_keys = |idx: usize| -> Result<Vec<Value>, ExecutionError> {
+            order_by_expr
+                .iter()
+                .map(|order_expr| evaluate(idx, &order_expr.expr))
+                .collect()
+        };
+
+        match top_k {
+            Some(k) if k < indices.len() => {
+                // 有界最大堆：堆顶是当前保留的行中排序最靠后的一行
+                let mut heap: BinaryHeap<SortEntry> = BinaryHeap::with_capacity(k + 1);
+                for &idx in indices {
+                    let entry = SortEntry {
+                        keys: sort_keys(idx)?,
+                        index: idx,
+                        asc: &asc,
+                    };
+                    if heap.len() < k {
+                        heap.push(entry);
+                    } else if heap.peek().is_some_and(|top| entry < *top) {
+                        heap.pop();
+                        heap.push(entry);
+                    }
+                }
+                Ok(heap
+                    .into_sorted_vec()
+                    .into_iter()
+                    .map(|entry| entry.index)
+                    .collect())
+            }
+            _ => {
+                let mut entries = indices
+                    .iter()
+                    .map(|&idx| Ok((sort_keys(idx)?, idx)))
+                    .collect::<Result<Vec<(Vec<Value>, usize)>, ExecutionError>>()?;
+                entries.sort_by(|(keys1, _), (keys2, _)| compare_sort_keys(keys1, keys2, &asc));
+                Ok(entries.into_iter().map(|(_, idx)| idx).collect())
+            }
+        }
+    }
+
+    /// 获取上下文中的数据源表
+    fn source_table<'a>(ctx: &EvalContext<'a>) -> Result<&'a Table, ExecutionError> {
+        ctx.table.ok_or(ExecutionError::ExecutionError(
+            "无法在无表环境下处理查询".to_string(),
+        ))
+    }
+
+    /// 计算通配符展开后的列
+    ///
+    /// `*` 展开为所有列；`t.*` 的限定名必须与表的别名（或无别名时的表名）一致。
+    /// 多表连接时由列作用域展开，`USING` 合并的列可能对应多个位置，取其中第一个非 NULL 的值。
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, Vec<usize>)>, ExecutionError>` - 每列的列名及其在行中的位置
+    fn wildcard_columns(
+        ctx: &EvalContext,
+        item: &SelectItem,
+    ) -> Result<Vec<(String, Vec<usize>)>, ExecutionError> {
+        let table = Self::source_table(ctx)?;
+        let qualifier = match item {
+            SelectItem::QualifiedWildcard(kind, _) => Some(match kind {
+                SelectItemQualifiedWildcardKind::ObjectName(name) => name.to_string(),
+                SelectItemQualifiedWildcardKind::Expr(expr) => expr.to_string(),
+            }),
+            _ => None,
+        };
+        if let Some(scope) = ctx.scope {
+            return scope.wildcard(qualifier.as_deref());
+        }
+        if let Some(qualifier) = qualifier {
+            if qualifier != ctx.alias.unwrap_or(&table.name) {
+                return Err(ExecutionError::ExecutionError(format!(
+                    "未知的表限定名 '{}'",
+                    qualifier
+                )));
             }
         }
+        Ok(table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, col)| (col.name.clone(), vec![idx]))
+            .collect())
     }
 }
+
+/// 按排序方向比较两组排序键
+///
+/// # Arguments
+///
+/// * `keys1`, `keys2` - 两行的排序键
+/// * `asc` - 每个排序键是否为升序
+pub(crate) fn compare_sort_keys(keys1: &[Value], keys2: &[Value], asc: &[bool]) -> Ordering {
+    for ((val1, val2), &asc) in keys1.iter().zip(keys2).zip(asc) {
+        let comparison = val1.partial_cmp(val2).unwrap_or(Ordering::Equal);
+        let ordered = if asc {
+            comparison
+        } else {
+            comparison.reverse()
+        };
+        if ordered != Ordering::Equal {
+            return ordered;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Top-k 排序时堆中的元素
+///
+/// 排序键相同时按行索引比较，使结果与稳定排序一致。
+struct SortEntry<'a> {
+    /// 排序键
+    keys: Vec<Value>,
+    /// 行索引
+    index: usize,
+    /// 每个排序键是否为升序
+    asc: &'a [bool],
+}
+
+impl Ord for SortEntry<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_sort_keys(&self.keys, &other.keys, self.asc).then(self.index.cmp(&other.index))
+    }
+}
+
+impl PartialOrd for SortEntry<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SortEntry<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortEntry<'_> {}
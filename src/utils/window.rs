@@ -0,0 +1,402 @@
+/// 窗口函数模块
+///
+/// 计算 `ROW_NUMBER`、`RANK`、`DENSE_RANK`、`LAG`、`LEAD` 以及聚合函数的窗口形式（如 `SUM(x) OVER (...)`）。
+/// 窗口函数在 `WHERE` 过滤之后、投影之前计算：先按 `PARTITION BY` 对行分区，
+/// 再用 [`QueryProcessor::sort_rows_by_order`] 按窗口的 `ORDER BY` 排序每个分区，最后为每一行计算函数值。
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{
+    Expr, Function, FunctionArg, FunctionArgExpr, FunctionArguments, OrderBy, OrderByKind, Query,
+    Visit, Visitor, WindowFrameBound, WindowFrameUnits, WindowSpec, WindowType,
+};
+
+use crate::executor::ExecutionError;
+use crate::model::Value;
+use crate::utils::aggregate;
+use crate::utils::expr_evaluator::{EvalContext, ExprEvaluator};
+use crate::utils::query_processor::QueryProcessor;
+
+/// 查询中所有窗口函数的计算结果
+///
+/// 以窗口函数表达式为键，保存每一行（以行在表中的索引标识）的函数值。
+#[derive(Debug, Default)]
+pub struct WindowValues {
+    /// 每个窗口函数表达式及其在各行上的值
+    values: Vec<(Expr, HashMap<usize, Value>)>,
+}
+
+impl WindowValues {
+    /// 计算窗口函数在给定行上的值
+    ///
+    /// # Arguments
+    /// * `ctx` - 求值上下文，其中的表为查询的数据源
+    /// * `indices` - 参与计算的行索引（通常为满足 `WHERE` 条件的行）
+    /// * `exprs` - 查询中的窗口函数表达式
+    ///
+    /// # Returns
+    /// * `Result<WindowValues, ExecutionError>` - 计算结果
+    pub fn compute(
+        ctx: &EvalContext,
+        indices: &[usize],
+        exprs: Vec<Expr>,
+    ) -> Result<Self, ExecutionError> {
+        let values = exprs
+            .into_iter()
+            .map(|expr| {
+                let Expr::Function(function) = &expr else {
+                    unreachable!("窗口函数表达式必须是函数调用")
+                };
+                let values = compute_function(ctx, indices, function)?;
+                Ok((expr, values))
+            })
+            .collect::<Result<_, ExecutionError>>()?;
+        Ok(Self { values })
+    }
+
+    /// 查找窗口函数在某一行上的值
+    ///
+    /// # Arguments
+    /// * `expr` - 窗口函数表达式
+    /// * `row_index` - 行在表中的索引
+    pub fn get(&self, expr: &Expr, row_index: usize) -> Option<&Value> {
+        self.values
+            .iter()
+            .find(|(window_expr, _)| window_expr == expr)
+            .and_then(|(_, values)| values.get(&row_index))
+    }
+}
+
+/// 收集表达式中的窗口函数，相同的窗口函数只保留一个
+///
+/// 子查询中的窗口函数属于子查询本身，不计入外层表达式。
+///
+/// # Arguments
+/// * `exprs` - 要检查的表达式
+pub fn collect_window_functions<'e>(exprs: impl IntoIterator<Item = &'e Expr>) -> Vec<Expr> {
+    let mut collector = WindowFunctionCollector {
+        query_depth: 0,
+        functions: Vec::new(),
+    };
+    for expr in exprs {
+        let _ = Visit::visit(expr, &mut collector);
+    }
+    collector.functions
+}
+
+/// 收集窗口函数的访问器
+struct WindowFunctionCollector {
+    /// 当前所在子查询的嵌套深度
+    query_depth: usize,
+    /// 已收集的窗口函数
+    functions: Vec<Expr>,
+}
+
+impl Visitor for WindowFunctionCollector {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        self.query_depth += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        self.query_depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        if let Expr::Function(Function { over: Some(_), .. }) = expr {
+            if self.query_depth == 0 && !self.functions.contains(expr) {
+                self.functions.push(expr.clone());
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// 支持的窗口函数
+enum WindowFunction<'f> {
+    /// `ROW_NUMBER()`：行在分区中的序号
+    RowNumber,
+    /// `RANK()`：排序值相同的行排名相同，之后的排名跳过并列的行数
+    Rank,
+    /// `DENSE_RANK()`：排序值相同的行排名相同，之后的排名连续
+    DenseRank,
+    /// `LAG(expr [, offset [, default]])` 和 `LEAD(...)`：分区中向前或向后第 offset 行的值
+    Offset {
+        expr: &'f Expr,
+        /// 向前为负，向后为正
+        offset: i64,
+        default: Option<&'f Expr>,
+    },
+    /// 聚合函数：对窗口框架内的行聚合，保存去掉 `OVER` 子句后的函数调用
+    Aggregate(Box<Expr>),
+}
+
+impl<'f> WindowFunction<'f> {
+    /// 根据函数调用解析窗口函数
+    fn parse(function: &'f Function) -> Result<Self, ExecutionError> {
+        let name = function.name.to_string().to_uppercase();
+        let args = match &function.args {
+            FunctionArguments::List(arg_list) => arg_list
+                .args
+                .iter()
+                .map(|arg| match arg {
+                    FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Ok(expr),
+                    _ => Err(ExecutionError::ExecutionError(format!(
+                        "窗口函数 {} 的参数无效",
+                        name
+                    ))),
+                })
+                .collect::<Result<Vec<&Expr>, ExecutionError>>(),
+            _ => Ok(vec![]),
+        };
+        match name.as_str() {
+            "ROW_NUMBER" | "RANK" | "DENSE_RANK" => {
+                if !args?.is_empty() {
+                    return Err(ExecutionError::ExecutionError(format!(
+                        "窗口函数 {} 不接受参数",
+                        name
+                    )));
+                }
+                Ok(match name.as_str() {
+                    "ROW_NUMBER" => Self::RowNumber,
+                    "RANK" => Self::Rank,
+                    _ => Self::DenseRank,
+                })
+            }
+            "LAG" | "LEAD" => {
+                let args = args?;
+                let (expr, offset, default) = match args.as_slice() {
+                    [expr] => (*expr, 1, None),
+                    [expr, offset] => (*expr, evaluate_offset(offset, &name)?, None),
+                    [expr, offset, default] => {
+                        (*expr, evaluate_offset(offset, &name)?, Some(*default))
+                    }
+                    _ => {
+                        return Err(ExecutionError::ExecutionError(format!(
+                            "窗口函数 {} 需要 1 到 3 个参数",
+                            name
+                        )))
+                    }
+                };
+                let offset = offset as i64;
+                Ok(Self::Offset {
+                    expr,
+                    offset: if name == "LAG" { -offset } else { offset },
+                    default,
+                })
+            }
+            _ if aggregate::is_aggregate_function(&name) => {
+                Ok(Self::Aggregate(Box::new(Expr::Function(Function {
+                    over: None,
+                    ..function.clone()
+                }))))
+            }
+            _ => Err(ExecutionError::ExecutionError(format!(
+                "不支持的窗口函数 {}",
+                name
+            ))),
+        }
+    }
+}
+
+/// 求值 `LAG`/`LEAD` 的偏移量或窗口框架的行数，必须是非负整数常量
+fn evaluate_offset(expr: &Expr, clause: &str) -> Result<usize, ExecutionError> {
+    match ExprEvaluator::evaluate(&EvalContext::default(), expr)? {
+        Value::Int(n) if n >= 0 => Ok(n as usize),
+        value => Err(ExecutionError::ExecutionError(format!(
+            "{} 的偏移量必须是非负整数，实际为 {}",
+            clause, value
+        ))),
+    }
+}
+
+/// 计算一个窗口函数在各行上的值
+fn compute_function(
+    ctx: &EvalContext,
+    indices: &[usize],
+    function: &Function,
+) -> Result<HashMap<usize, Value>, ExecutionError> {
+    let spec = match &function.over {
+        Some(WindowType::WindowSpec(spec)) if spec.window_name.is_none() => spec,
+        _ => {
+            return Err(ExecutionError::ExecutionError(format!(
+                "不支持命名窗口: {}",
+                function
+            )))
+        }
+    };
+    let window_function = WindowFunction::parse(function)?;
+    let order_by = (!spec.order_by.is_empty()).then(|| OrderBy {
+        kind: OrderByKind::Expressions(spec.order_by.clone()),
+        interpolate: None,
+    });
+
+    let mut values = HashMap::with_capacity(indices.len());
+    for partition in partition_rows(ctx, indices, &spec.partition_by)? {
+        let sorted = QueryProcessor::sort_rows_by_order(ctx, &partition, &order_by, None)?;
+        let peers = PeerGroups::new(ctx, &sorted, spec)?;
+        for (pos, &idx) in sorted.iter().enumerate() {
+            let value = match &window_function {
+                WindowFunction::RowNumber => Value::Int(pos as i64 + 1),
+                WindowFunction::Rank => Value::Int(peers.start[pos] as i64 + 1),
+                WindowFunction::DenseRank => Value::Int(peers.number[pos] as i64),
+                WindowFunction::Offset {
+                    expr,
+                    offset,
+                    default,
+                } => match usize::try_from(pos as i64 + offset)
+                    .ok()
+                    .and_then(|target| sorted.get(target))
+                {
+                    Some(&target) => ExprEvaluator::evaluate(&ctx.with_row_at(target), expr)?,
+                    None => match default {
+                        Some(default) => ExprEvaluator::evaluate(&ctx.with_row_at(idx), default)?,
+                        None => Value::Null,
+                    },
+                },
+                WindowFunction::Aggregate(aggregate) => {
+                    let (start, end) = frame_bounds(spec, &peers, pos, sorted.len())?;
+                    let frame = &sorted[start..end.max(start)];
+                    ExprEvaluator::evaluate(&ctx.with_group(frame), aggregate)?
+                }
+            };
+            values.insert(idx, value);
+        }
+    }
+    Ok(values)
+}
+
+/// 按 `PARTITION BY` 表达式对行分区，分区按首次出现的顺序排列
+fn partition_rows(
+    ctx: &EvalContext,
+    indices: &[usize],
+    partition_by: &[Expr],
+) -> Result<Vec<Vec<usize>>, ExecutionError> {
+    let mut partitions: Vec<Vec<usize>> = Vec::new();
+    let mut positions: HashMap<Vec<Value>, usize> = HashMap::new();
+    for &idx in indices {
+        let row_ctx = ctx.with_row_at(idx);
+        let key = partition_by
+            .iter()
+            .map(|expr| ExprEvaluator::evaluate(&row_ctx, expr))
+            .collect::<Result<Vec<Value>, ExecutionError>>()?;
+        let pos = *positions.entry(key).or_insert_with(|| {
+            partitions.push(Vec::new());
+            partitions.len() - 1
+        });
+        partitions[pos].push(idx);
+    }
+    Ok(partitions)
+}
+
+/// 排序后的分区中排序值相同的行（同级行）的划分
+struct PeerGroups {
+    /// 每一行所在同级行组的起始位置
+    start: Vec<usize>,
+    /// 每一行所在同级行组的结束位置（不含）
+    end: Vec<usize>,
+    /// 每一行所在同级行组的序号，从 1 开始
+    number: Vec<usize>,
+}
+
+impl PeerGroups {
+    /// 根据窗口的 `ORDER BY` 划分同级行；没有 `ORDER BY` 时整个分区为一组同级行
+    fn new(ctx: &EvalContext, sorted: &[usize], spec: &WindowSpec) -> Result<Self, ExecutionError> {
+        let keys = sorted
+            .iter()
+            .map(|&idx| {
+                let row_ctx = ctx.with_row_at(idx);
+                spec.order_by
+                    .iter()
+                    .map(|order_expr| ExprEvaluator::evaluate(&row_ctx, &order_expr.expr))
+                    .collect::<Result<Vec<Value>, ExecutionError>>()
+            })
+            .collect::<Result<Vec<Vec<Value>>, ExecutionError>>()?;
+
+        let len = sorted.len();
+        let mut start = vec![0; len];
+        let mut end = vec![len; len];
+        let mut number = vec![1; len];
+        for pos in 1..len {
+            if keys[pos] == keys[pos - 1] {
+                start[pos] = start[pos - 1];
+                number[pos] = number[pos - 1];
+            } else {
+                start[pos] = pos;
+                number[pos] = number[pos - 1] + 1;
+            }
+        }
+        for pos in (0..len.saturating_sub(1)).rev() {
+            if keys[pos] == keys[pos + 1] {
+                end[pos] = end[pos + 1];
+            } else {
+                end[pos] = pos + 1;
+            }
+        }
+        Ok(Self { start, end, number })
+    }
+}
+
+/// 计算分区中第 `pos` 行的窗口框架，返回框架在排序后分区中的起止位置（不含结束位置）
+///
+/// 未指定框架时，有 `ORDER BY` 的窗口默认为 `RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`，
+/// 即从分区开头到当前行的最后一个同级行；没有 `ORDER BY` 的窗口为整个分区。
+/// `RANGE` 框架只支持 `UNBOUNDED` 和 `CURRENT ROW` 边界，不支持 `GROUPS` 框架。
+fn frame_bounds(
+    spec: &WindowSpec,
+    peers: &PeerGroups,
+    pos: usize,
+    len: usize,
+) -> Result<(usize, usize), ExecutionError> {
+    let Some(frame) = &spec.window_frame else {
+        return Ok(if spec.order_by.is_empty() {
+            (0, len)
+        } else {
+            (0, peers.end[pos])
+        });
+    };
+    let end_bound = frame
+        .end_bound
+        .as_ref()
+        .unwrap_or(&WindowFrameBound::CurrentRow);
+    match frame.units {
+        WindowFrameUnits::Rows => {
+            let bound =
+                |bound: &WindowFrameBound, is_start: bool| -> Result<usize, ExecutionError> {
+                    // 结束位置不含在框架内，因此结束边界需要加一
+                    let exclusive = usize::from(!is_start);
+                    Ok(match bound {
+                        WindowFrameBound::CurrentRow => pos + exclusive,
+                        WindowFrameBound::Preceding(None) => 0,
+                        WindowFrameBound::Following(None) => len,
+                        WindowFrameBound::Preceding(Some(n)) => {
+                            (pos + exclusive).saturating_sub(evaluate_offset(n, "ROWS")?)
+                        }
+                        WindowFrameBound::Following(Some(n)) => {
+                            (pos + exclusive + evaluate_offset(n, "ROWS")?).min(len)
+                        }
+                    })
+                };
+            Ok((bound(&frame.start_bound, true)?, bound(end_bound, false)?))
+        }
+        WindowFrameUnits::Range => {
+            let bound = |bound: &WindowFrameBound, is_start: bool| match bound {
+                WindowFrameBound::CurrentRow if is_start => Ok(peers.start[pos]),
+                WindowFrameBound::CurrentRow => Ok(peers.end[pos]),
+                WindowFrameBound::Preceding(None) => Ok(0),
+                WindowFrameBound::Following(None) => Ok(len),
+                _ => Err(ExecutionError::ExecutionError(format!(
+                    "不支持带偏移量的 RANGE 窗口框架: {}",
+                    bound
+                ))),
+            };
+            Ok((bound(&frame.start_bound, true)?, bound(end_bound, false)?))
+        }
+        WindowFrameUnits::Groups => Err(ExecutionError::ExecutionError(
+            "不支持 GROUPS 窗口框架".to_string(),
+        )),
+    }
+}
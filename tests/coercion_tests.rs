@@ -0,0 +1,54 @@
+use simple_db::execute_sql;
+use simple_db::executor::TABLES;
+use simple_db::model::Value;
+
+/// 读取表中的所有数据行
+fn table_rows(table_name: &str) -> Vec<Vec<Value>> {
+    TABLES.lock().unwrap()[table_name].data.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_range_integers_are_rejected() {
+        assert!(execute_sql(
+            "CREATE TABLE coercion_range (id INT, b BIGINT);"
+        ));
+
+        // 严格模式下超出 BIGINT 范围的字符串和浮点数不能写入
+        assert!(!execute_sql(
+            "INSERT INTO coercion_range VALUES (1, '99999999999999999999');"
+        ));
+        assert!(!execute_sql("INSERT INTO coercion_range VALUES (2, 1e30);"));
+        assert!(!execute_sql(
+            "INSERT INTO coercion_range VALUES (3, -1e30);"
+        ));
+        assert!(execute_sql(
+            "INSERT INTO coercion_range VALUES (4, '9223372036854775807');"
+        ));
+        assert_eq!(
+            table_rows("coercion_range"),
+            vec![vec![Value::Int(4), Value::Int(i64::MAX)]]
+        );
+
+        // 显式转换不受严格模式影响，但超出范围时同样报错
+        assert!(!execute_sql(
+            "SELECT CAST('99999999999999999999' AS SIGNED);"
+        ));
+        assert!(!execute_sql("SELECT CAST(1e30 AS SIGNED);"));
+        assert!(!execute_sql("SELECT CAST(1e400 AS SIGNED);"));
+        assert!(execute_sql("SELECT CAST('42abc' AS SIGNED);"));
+
+        // 非严格模式下截断到 BIGINT 的范围
+        assert!(execute_sql("SET sql_mode = '';"));
+        let inserted = execute_sql("INSERT INTO coercion_range VALUES (5, 1e30);");
+        assert!(execute_sql("SET sql_mode = 'STRICT_TRANS_TABLES';"));
+        assert!(inserted);
+        assert_eq!(
+            table_rows("coercion_range")[1],
+            vec![Value::Int(5), Value::Int(i64::MAX)]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use simple_db::execute_sql;
+use simple_db::executor::prepare::prepare;
+use simple_db::executor::TABLES;
+use simple_db::model::Value;
+
+/// 读取表中的所有数据行
+fn table_rows(table_name: &str) -> Vec<Vec<Value>> {
+    TABLES.lock().unwrap()[table_name].data.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepared_statement_is_executed_repeatedly() {
+        assert!(execute_sql(
+            "CREATE TABLE prepared_users (id INT PRIMARY KEY, name VARCHAR(50), score INT);"
+        ));
+        let insert = prepare("INSERT INTO prepared_users VALUES (?, ?, ?)").unwrap();
+        assert_eq!(insert.parameter_count(), 3);
+        insert.execute(&[&1, &"alice", &10]).unwrap();
+        insert
+            .execute(&[&2, &"bob'); DROP TABLE prepared_users; --", &Value::Null])
+            .unwrap();
+
+        // 同一个编号的参数可以出现多次
+        let update = prepare("UPDATE prepared_users SET score = $2 + $2 WHERE id = $1").unwrap();
+        assert_eq!(update.parameter_count(), 2);
+        update.execute(&[&2, &21]).unwrap();
+
+        assert_eq!(
+            table_rows("prepared_users"),
+            vec![
+                vec![
+                    Value::Int(1),
+                    Value::Varchar("alice".to_string()),
+                    Value::Int(10)
+                ],
+                vec![
+                    Value::Int(2),
+                    Value::Varchar("bob'); DROP TABLE prepared_users; --".to_string()),
+                    Value::Int(42)
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn prepared_query_returns_result() {
+        assert!(execute_sql(
+            "CREATE TABLE prepared_scores (id INT, name VARCHAR(50), score INT);"
+        ));
+        assert!(execute_sql(
+            "INSERT INTO prepared_scores VALUES (1, 'alice', 10), (2, 'bob', 20), (3, 'carol', 30);"
+        ));
+
+        let select = prepare(
+            "SELECT name, score * 2 AS doubled FROM prepared_scores WHERE score >= ? ORDER BY id",
+        )
+        .unwrap();
+        let result = select.query(&[&20]).unwrap();
+        assert_eq!(result.columns, vec!["name", "doubled"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![Value::Varchar("bob".to_string()), Value::Int(40)],
+                vec![Value::Varchar("carol".to_string()), Value::Int(60)],
+            ]
+        );
+
+        // 同一条语句可以绑定不同的参数再次查询
+        let result = select.query(&[&100]).unwrap();
+        assert_eq!(result.columns, vec!["name", "doubled"]);
+        assert!(result.rows.is_empty());
+
+        // 非查询语句和参数个数不符时返回错误
+        let delete = prepare("DELETE FROM prepared_scores WHERE id = ?").unwrap();
+        assert!(delete.query(&[&1]).is_err());
+        assert!(select.query(&[]).is_err());
+        assert_eq!(table_rows("prepared_scores").len(), 3);
+    }
+
+    #[test]
+    fn invalid_prepared_statements_are_rejected() {
+        let select = prepare("SELECT ? + 1").unwrap();
+        assert!(select.execute(&[]).is_err());
+        assert!(select.execute(&[&1, &2]).is_err());
+        assert!(select.execute(&[&f64::INFINITY]).is_err());
+        assert!(select.execute(&[&f64::NAN]).is_err());
+        assert!(select.execute(&[&1.5]).is_ok());
+
+        assert!(prepare("SELECT ?, $1").is_err());
+        assert!(prepare("SELECT 1; SELECT 2").is_err());
+        assert!(prepare("SELEC 1").is_err());
+        assert!(prepare("EXECUTE stmt USING 1").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use simple_db::model::convert::{FromRow, FromValue, Row, ToValue};
+use simple_db::model::Value;
+use simple_db::Database;
+
+#[derive(Debug, PartialEq, FromRow)]
+struct User {
+    id: i64,
+    name: String,
+    #[column("is_adult")]
+    adult: bool,
+    email: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_as_decodes_rows_into_structs() {
+        let db = Database::new();
+        db.execute(
+            "CREATE TABLE typed_users (id INT PRIMARY KEY, name VARCHAR(20), age INT, email VARCHAR(50))",
+            &[],
+        )
+        .unwrap();
+        let email: Option<&str> = None;
+        db.execute(
+            "INSERT INTO typed_users VALUES (?, ?, ?, ?), (?, ?, ?, ?)",
+            &[
+                &1,
+                &"alice",
+                &30,
+                &"alice@example.com",
+                &2,
+                &"bob".to_string(),
+                &12,
+                &email,
+            ],
+        )
+        .unwrap();
+
+        let users: Vec<User> = db
+            .query_as(
+                "SELECT id, name, age >= 18 AS is_adult, email FROM typed_users WHERE id <= ? ORDER BY id",
+                &[&10],
+            )
+            .unwrap();
+        assert_eq!(
+            users,
+            vec![
+                User {
+                    id: 1,
+                    name: "alice".to_string(),
+                    adult: true,
+                    email: Some("alice@example.com".to_string()),
+                },
+                User {
+                    id: 2,
+                    name: "bob".to_string(),
+                    adult: false,
+                    email: None,
+                },
+            ]
+        );
+
+        let result = db
+            .query("SELECT COUNT(*), AVG(age) FROM typed_users", &[])
+            .unwrap();
+        let row = Row::new(&result.columns, &result.rows[0]);
+        assert_eq!(row.get_index::<i64>(0).unwrap(), 2);
+        assert_eq!(row.get_index::<f64>(1).unwrap(), 21.0);
+
+        // 缺少列、值为 NULL 或类型不符时返回错误
+        assert!(db
+            .query_as::<User>("SELECT id, name FROM typed_users", &[])
+            .is_err());
+        assert!(db
+            .query_as::<User>(
+                "SELECT id, email AS name, TRUE AS is_adult, email FROM typed_users",
+                &[]
+            )
+            .is_err());
+        assert!(db
+            .query_as::<User>(
+                "SELECT name AS id, name, TRUE AS is_adult, email FROM typed_users",
+                &[]
+            )
+            .is_err());
+        assert!(db.query("DELETE FROM typed_users", &[]).is_err());
+    }
+
+    #[test]
+    fn values_convert_to_and_from_rust_types() {
+        assert_eq!(i64::from_value(&Value::Int(7)).unwrap(), 7);
+        assert!(bool::from_value(&Value::Int(1)).unwrap());
+        assert!(bool::from_value(&Value::Int(2)).is_err());
+        assert!(String::from_value(&Value::Null).is_err());
+        assert_eq!(Option::<i64>::from_value(&Value::Null).unwrap(), None);
+
+        assert_eq!("text".to_value(), Value::Varchar("text".to_string()));
+        assert_eq!(Some(3i64).to_value(), Value::Int(3));
+        assert_eq!(None::<bool>.to_value(), Value::Null);
+        assert_eq!(2.5.to_value(), Value::Float(2.5));
+    }
+}
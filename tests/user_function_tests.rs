@@ -0,0 +1,130 @@
+use simple_db::execute_sql;
+use simple_db::executor::{ExecutionError, TABLES};
+use simple_db::model::Value;
+use simple_db::utils::aggregate::{self, AggregateFunction};
+use simple_db::utils::function::{self, Arity};
+
+/// 读取表中的所有数据行
+fn table_rows(table_name: &str) -> Vec<Vec<Value>> {
+    TABLES.lock().unwrap()[table_name].data.clone()
+}
+
+/// 按顺序执行多条 SQL 语句，任一语句失败时 panic
+fn execute_all(statements: &[&str]) {
+    for statement in statements {
+        assert!(execute_sql(statement), "执行失败: {}", statement);
+    }
+}
+
+/// 所有参数值的乘积，忽略 NULL
+struct Product;
+
+impl AggregateFunction for Product {
+    type State = Option<i64>;
+
+    fn init(&self) -> Self::State {
+        None
+    }
+
+    fn step(&self, state: &mut Self::State, value: &Value) -> Result<(), ExecutionError> {
+        match value {
+            Value::Null => {}
+            Value::Int(i) => *state = Some(state.unwrap_or(1) * i),
+            _ => {
+                return Err(ExecutionError::ExecutionError(format!(
+                    "PRODUCT 的参数必须是整数，实际为 {}",
+                    value
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&self, state: &Self::State) -> Result<Value, ExecutionError> {
+        Ok(state.map_or(Value::Null, Value::Int))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalar_function_is_callable_from_sql() {
+        function::register_scalar_function("double_it", Arity::Exact(1), |args| {
+            match &args[0] {
+                Value::Int(i) => Ok(Value::Int(i * 2)),
+                // NULL 原样传给实现
+                Value::Null => Ok(Value::Int(-1)),
+                value => Err(ExecutionError::ExecutionError(format!(
+                    "DOUBLE_IT 的参数必须是整数，实际为 {}",
+                    value
+                ))),
+            }
+        })
+        .unwrap();
+
+        execute_all(&[
+            "CREATE TABLE udf_scalar (id INT, price INT);",
+            "INSERT INTO udf_scalar VALUES (1, 10), (2, 20), (3, NULL);",
+            "UPDATE udf_scalar SET price = DOUBLE_IT(price) WHERE Double_It(id) > 2;",
+        ]);
+        assert_eq!(
+            table_rows("udf_scalar"),
+            vec![
+                vec![Value::Int(1), Value::Int(10)],
+                vec![Value::Int(2), Value::Int(40)],
+                vec![Value::Int(3), Value::Int(-1)],
+            ]
+        );
+
+        assert!(!execute_sql("SELECT double_it(1, 2);"));
+        assert!(!execute_sql("SELECT double_it('a');"));
+        assert_eq!(
+            function::call("double_it", &[Value::Int(21)]).unwrap(),
+            Value::Int(42)
+        );
+    }
+
+    #[test]
+    fn aggregate_function_is_callable_from_sql() {
+        aggregate::register_aggregate_function("product", Product).unwrap();
+
+        execute_all(&[
+            "CREATE TABLE udf_aggregate (grp INT, v INT, total INT);",
+            "INSERT INTO udf_aggregate VALUES (1, 2, 0), (1, 3, 0), (1, 3, 0), (2, 5, 0), (2, NULL, 0), (3, NULL, 0);",
+            "UPDATE udf_aggregate SET total = (SELECT PRODUCT(v) FROM udf_aggregate);",
+        ]);
+        assert!(table_rows("udf_aggregate")
+            .iter()
+            .all(|row| row[2] == Value::Int(90)));
+
+        execute_all(&[
+            "UPDATE udf_aggregate SET total = (SELECT product(DISTINCT v) FROM udf_aggregate);",
+        ]);
+        assert_eq!(table_rows("udf_aggregate")[0][2], Value::Int(30));
+
+        // 分组的乘积：1 组为 18，2 组为 5，3 组为 NULL
+        execute_all(&[
+            "DELETE FROM udf_aggregate WHERE grp IN (SELECT grp FROM udf_aggregate GROUP BY grp HAVING product(v) > 10 OR product(v) IS NULL);",
+        ]);
+        let groups: Vec<Value> = table_rows("udf_aggregate")
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect();
+        assert_eq!(groups, vec![Value::Int(2), Value::Int(2)]);
+
+        assert!(!execute_sql("SELECT product('a');"));
+    }
+
+    #[test]
+    fn builtin_functions_cannot_be_replaced() {
+        let identity = |args: &[Value]| -> Result<Value, ExecutionError> { Ok(args[0].clone()) };
+        assert!(function::register_scalar_function("upper", Arity::Exact(1), identity).is_err());
+        assert!(function::register_scalar_function("sum", Arity::Exact(1), identity).is_err());
+        assert!(function::register_scalar_function("coalesce", Arity::Exact(1), identity).is_err());
+        assert!(function::register_scalar_function("bad-name", Arity::Exact(1), identity).is_err());
+        assert!(aggregate::register_aggregate_function("count", Product).is_err());
+        assert!(aggregate::register_aggregate_function("concat", Product).is_err());
+    }
+}